@@ -0,0 +1,64 @@
+use std::{env, fs, path::Path, process::Command};
+
+/// Generates the solution registry from the `aoc2023-*` dependencies declared in
+/// the manifest. Cargo can only link crates the manifest names, so a new day
+/// needs its crate and a dependency entry; day crates without one are reported
+/// as build warnings.
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-changed=crates");
+
+    let manifest = fs::read_to_string("Cargo.toml").expect("Failed to read manifest");
+    let manifest: toml::Table = manifest.parse().expect("Failed to parse manifest");
+
+    let dependencies = manifest
+        .get("dependencies")
+        .and_then(|dependencies| dependencies.as_table())
+        .expect("Manifest has no dependencies");
+
+    let mut registry =
+        String::from("pub static SOLUTIONS: &[&dyn aoc_util::solution::DynSolution] = &[\n");
+
+    for name in dependencies
+        .keys()
+        .filter(|name| name.starts_with("aoc2023-"))
+    {
+        registry.push_str(&format!("    &{}::Day,\n", name.replace('-', "_")));
+    }
+
+    registry.push_str("];\n");
+
+    for entry in fs::read_dir("crates").expect("Failed to read crates directory") {
+        let directory = entry.expect("Failed to read crates directory").file_name();
+        let Some(day) = directory
+            .to_str()
+            .and_then(|name| name.strip_prefix("day-"))
+        else {
+            continue;
+        };
+
+        let name = format!("aoc2023-{day}");
+        if !dependencies.contains_key(&name) {
+            println!(
+                "cargo:warning=crates/day-{day} is not registered, add `{name} = {{ path = \"crates/day-{day}\" }}` to the [dependencies] of Cargo.toml"
+            );
+        }
+    }
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    fs::write(Path::new(&out_dir).join("solutions.rs"), registry)
+        .expect("Failed to write solution registry");
+
+    // Recorded with every benchmark in the history
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map_or_else(
+            || "unknown".to_string(),
+            |version| version.trim().to_string(),
+        );
+    println!("cargo:rustc-env=AOC2023_RUSTC_VERSION={version}");
+}
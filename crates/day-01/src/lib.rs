@@ -1,28 +1,62 @@
+use aoc_util::{
+    error::SolveError,
+    solution::{Example, Solution},
+};
 use rayon::{iter::ParallelIterator, str::ParallelString};
 use regex::Regex;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 1;
+    const TITLE: &'static str = "Trebuchet?!";
+
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT1, 142, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT2, 281, solve_part2)]
+    }
+}
+
+fn solve_part1(input: &str) -> Result<u32, SolveError> {
+    Ok(parse_part1(input)?.into_iter().sum())
+}
+
+fn solve_part2(input: &str) -> Result<u32, SolveError> {
+    Ok(parse_part2(input)?.into_iter().sum())
 }
 
-fn solve_part1(input: &str) -> u32 {
+/// Calibration values made of the first and last digit on every line.
+#[tracing::instrument(skip_all)]
+fn parse_part1(input: &str) -> Result<Vec<u32>, SolveError> {
     input
         .lines()
         .map(|line| {
             let mut digits = line.chars().filter_map(|c| c.to_digit(10));
-            let first = digits.next().unwrap();
-            let last = digits.last().unwrap_or(first);
-            (first, last)
+            let first = digits.next().ok_or_else(|| no_digit(input, line))?;
+            let last = digits.next_back().unwrap_or(first);
+            Ok(first * 10 + last)
         })
-        .map(|(a, b)| a * 10 + b)
-        .sum()
-}
-
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+        .collect()
 }
 
-fn solve_part2(input: &str) -> u32 {
+/// Calibration values made of the first and last digit on every line, where
+/// digits may also be spelled out.
+#[tracing::instrument(skip_all)]
+fn parse_part2(input: &str) -> Result<Vec<u32>, SolveError> {
     let re = Regex::new(r"^([0-9]|one|two|three|four|five|six|seven|eight|nine)").unwrap();
     input
         .par_lines()
@@ -32,12 +66,15 @@ fn solve_part2(input: &str) -> u32 {
                 .filter_map(|slice| re.captures(slice)?.get(1).map(|m| m.as_str()))
                 .map(digit);
 
-            let first = digits.next().unwrap();
-            let last = digits.last().unwrap_or(first);
-            (first, last)
+            let first = digits.next().ok_or_else(|| no_digit(input, line))?;
+            let last = digits.next_back().unwrap_or(first);
+            Ok(first * 10 + last)
         })
-        .map(|(a, b)| a * 10 + b)
-        .sum()
+        .collect()
+}
+
+fn no_digit(input: &str, line: &str) -> SolveError {
+    SolveError::at(input, line, "Expected at least one digit")
 }
 
 fn digit(s: &str) -> u32 {
@@ -56,17 +93,13 @@ fn digit(s: &str) -> u32 {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT1: &str = "1abc2
+const EXAMPLE_INPUT1: &str = "1abc2
 pqr3stu8vwx
 a1b2c3d4e5f
 treb7uchet
 ";
 
-    const TEST_INPUT2: &str = "two1nine
+const EXAMPLE_INPUT2: &str = "two1nine
 eightwothree
 abcone2threexyz
 xtwone3four
@@ -75,15 +108,19 @@ zoneight234
 7pqrstsixteen
 ";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT1);
+        let solution = solve_part1(EXAMPLE_INPUT1).unwrap();
         assert_eq!(solution, 142);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT2);
+        let solution = solve_part2(EXAMPLE_INPUT2).unwrap();
         assert_eq!(solution, 281);
     }
 }
@@ -1,36 +1,79 @@
 use ahash::AHashMap as HashMap;
 
-use itertools::Itertools;
+use aoc_util::{
+    error::SolveError,
+    solution::{Example, Solution},
+};
 use rayon::{iter::ParallelIterator, str::ParallelString};
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 2;
+    const TITLE: &'static str = "Cube Conundrum";
+
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 8, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 2286, solve_part2)]
+    }
 }
 
-fn parse(line: &str) -> Game {
-    let (prefix, line) = line.split_once(": ").unwrap();
-    let id = prefix[5..].parse::<u32>().unwrap();
-    let sets = line
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Vec<Game>, SolveError> {
+    input
+        .par_lines()
+        .map(|line| parse_game(input, line))
+        .collect()
+}
+
+fn parse_game<'a>(input: &'a str, line: &'a str) -> Result<Game, SolveError> {
+    let (prefix, sets) = line
+        .split_once(": ")
+        .ok_or_else(|| SolveError::at(input, line, "Expected `Game <id>: `"))?;
+    let id = prefix
+        .strip_prefix("Game ")
+        .and_then(|id| id.parse::<u32>().ok())
+        .ok_or_else(|| SolveError::at(input, prefix, "Expected `Game <id>`"))?;
+    let sets = sets
         .split("; ")
         .map(|part| {
             part.split(", ")
                 .map(|entry| {
-                    let (count, color) = entry.split_once(' ').unwrap();
-                    let count = count.parse::<u32>().unwrap();
-                    (color.to_string(), count)
+                    let count = entry
+                        .split_once(' ')
+                        .and_then(|(count, color)| Some((count.parse::<u32>().ok()?, color)));
+                    let (count, color) = count.ok_or_else(|| {
+                        SolveError::at(input, entry, "Expected `<count> <color>`")
+                    })?;
+                    Ok::<_, SolveError>((color.to_string(), count))
                 })
-                .collect_vec()
+                .collect::<Result<Vec<_>, _>>()
         })
-        .collect_vec();
-    Game { id, sets }
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Game { id, sets })
 }
 
-fn solve_part1(input: &str) -> u32 {
+fn solve_part1(input: &str) -> Result<u32, SolveError> {
     let maximums: HashMap<&str, u32> = HashMap::from([("red", 12), ("green", 13), ("blue", 14)]);
 
-    input
-        .par_lines()
-        .map(parse)
+    let games = parse(input)?;
+
+    let sum = games
+        .iter()
         .filter(|game| {
             for (color, count) in game.sets.iter().flat_map(|set| set.iter()) {
                 if *count > maximums.get(color.as_str()).copied().unwrap_or(0) {
@@ -41,17 +84,16 @@ fn solve_part1(input: &str) -> u32 {
             true
         })
         .map(|game| game.id)
-        .sum()
-}
+        .sum();
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+    Ok(sum)
 }
 
-fn solve_part2(input: &str) -> u32 {
-    input
-        .par_lines()
-        .map(parse)
+fn solve_part2(input: &str) -> Result<u32, SolveError> {
+    let games = parse(input)?;
+
+    let sum = games
+        .iter()
         .map(|game| {
             let mut maximums: HashMap<&str, u32> = HashMap::new();
 
@@ -64,7 +106,9 @@ fn solve_part2(input: &str) -> u32 {
 
             maximums.values().product::<u32>()
         })
-        .sum()
+        .sum();
+
+    Ok(sum)
 }
 
 struct Game {
@@ -72,26 +116,26 @@ struct Game {
     sets: Vec<Vec<(String, u32)>>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+const EXAMPLE_INPUT: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
 ";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 8);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 2286);
     }
 }
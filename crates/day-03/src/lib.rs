@@ -1,14 +1,40 @@
+use aoc_util::{
+    error::SolveError,
+    solution::{Example, Solution},
+};
 use nalgebra::{vector, Vector2};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 3;
+    const TITLE: &'static str = "Gear Ratios";
+
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 4361, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 467835, solve_part2)]
+    }
 }
 
-fn solve_part1(input: &str) -> u32 {
-    let schematic = parse(input);
+fn solve_part1(input: &str) -> Result<u32, SolveError> {
+    let schematic = parse(input)?;
 
-    schematic
+    let sum = schematic
         .numbers
         .par_iter()
         .filter(|(pos, slice, _)| {
@@ -20,17 +46,15 @@ fn solve_part1(input: &str) -> u32 {
                 .any(|(pos, _)| y_range.contains(&pos.y) && x_range.contains(&pos.x))
         })
         .map(|(_, _, value)| *value)
-        .sum()
-}
+        .sum();
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+    Ok(sum)
 }
 
-fn solve_part2(input: &str) -> u32 {
-    let schematic = parse(input);
+fn solve_part2(input: &str) -> Result<u32, SolveError> {
+    let schematic = parse(input)?;
 
-    schematic
+    let sum = schematic
         .symbols
         .par_iter()
         .filter(|(_, symbol)| *symbol == "*")
@@ -52,7 +76,9 @@ fn solve_part2(input: &str) -> u32 {
                 _ => None,
             }
         })
-        .sum()
+        .sum();
+
+    Ok(sum)
 }
 
 type Pos = Vector2<usize>;
@@ -63,7 +89,8 @@ struct Schematic<'a> {
     numbers: Vec<(Pos, &'a str, u32)>,
 }
 
-fn parse(input: &str) -> Schematic {
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Schematic<'_>, SolveError> {
     let mut schematic = Schematic::default();
 
     enum State {
@@ -73,7 +100,7 @@ fn parse(input: &str) -> Schematic {
 
     for (y, line) in input.lines().enumerate() {
         let mut state = State::Default;
-        for (x, cur) in line.chars().enumerate() {
+        for (x, cur) in line.char_indices() {
             if let State::Default = state {
                 if cur.is_ascii_digit() {
                     state = State::Number(x);
@@ -82,7 +109,7 @@ fn parse(input: &str) -> Schematic {
                 if !cur.is_ascii_digit() {
                     let pos = vector![start, y];
                     let number = &line[start..x];
-                    let value = number.parse().unwrap();
+                    let value = parse_number(input, number)?;
                     schematic.numbers.push((pos, number, value));
                     state = State::Default;
                 }
@@ -92,30 +119,37 @@ fn parse(input: &str) -> Schematic {
                 continue;
             }
 
+            if !cur.is_ascii() {
+                return Err(SolveError::at(
+                    input,
+                    &line[x..],
+                    "Expected an ASCII symbol",
+                ));
+            }
+
             let pos = vector![x, y];
             let symbol = &line[x..=x];
-            assert!(symbol.is_ascii());
             schematic.symbols.push((pos, symbol));
         }
 
         if let State::Number(start) = state {
             let pos = vector![start, y];
             let number = &line[start..];
-            let value = number.parse().unwrap();
+            let value = parse_number(input, number)?;
             schematic.numbers.push((pos, number, value));
         }
     }
 
-    schematic
+    Ok(schematic)
 }
 
-#[cfg(test)]
-mod tests {
-    use rstest::rstest;
-
-    use super::*;
+fn parse_number(input: &str, number: &str) -> Result<u32, SolveError> {
+    number
+        .parse()
+        .map_err(|_| SolveError::at(input, number, "Number out of range"))
+}
 
-    const TEST_INPUT: &str = "467..114..
+const EXAMPLE_INPUT: &str = "467..114..
 ...*......
 ..35..633.
 ......#...
@@ -127,9 +161,15 @@ mod tests {
 .664.598..
 ";
 
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 4361);
     }
 
@@ -156,13 +196,13 @@ mod tests {
         925
     )]
     fn extras_part1(#[case] input: &str, #[case] expected: u32) {
-        let solution = solve_part1(input);
+        let solution = solve_part1(input).unwrap();
         assert_eq!(solution, expected);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 467835);
     }
 
@@ -189,7 +229,7 @@ mod tests {
         6756
     )]
     fn extras_part2(#[case] input: &str, #[case] expected: u32) {
-        let solution = solve_part2(input);
+        let solution = solve_part2(input).unwrap();
         assert_eq!(solution, expected);
     }
 }
@@ -1,6 +1,10 @@
 use std::num::NonZeroU32;
 
-use aoc_util::cache::Cache;
+use aoc_util::{
+    cache::Cache,
+    error::{Located, SolveError},
+    solution::{Example, Solution},
+};
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
@@ -11,30 +15,51 @@ use nom::{
 };
 use nom_supreme::final_parser::final_parser;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 4;
+    const TITLE: &'static str = "Scratchcards";
+
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 13, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 30, solve_part2)]
+    }
 }
 
-fn solve_part1(input: &str) -> u32 {
-    input
-        .lines()
-        .map(|line| parse(line).unwrap())
-        .map(|card| card.score())
-        .sum()
+fn solve_part1(input: &str) -> Result<u32, SolveError> {
+    let cards = parse(input)?;
+    Ok(cards.iter().map(Card::score).sum())
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+fn solve_part2(input: &str) -> Result<u32, SolveError> {
+    let cards = parse(input)?;
+    Ok(count_cards(&cards))
 }
 
-fn solve_part2(input: &str) -> u32 {
-    let dependencies = input
-        .lines()
-        .map(|line| parse(line).unwrap())
-        .map(|card| card.winning_number_count())
+/// Counts the original cards together with all the copies they win.
+#[tracing::instrument(skip_all)]
+fn count_cards(cards: &[Card]) -> u32 {
+    let dependencies = cards
+        .iter()
+        .map(Card::winning_number_count)
         .enumerate()
         .map(|(idx, count)| ((idx + 1)..=(idx + count as usize)).collect_vec())
-        .collect_vec();
+        .collect::<Vec<_>>();
 
     fn count_internal<C>(idx: usize, dependencies: &Vec<Vec<usize>>, cache: &mut C) -> u32
     where
@@ -85,7 +110,15 @@ impl Card {
     }
 }
 
-fn parse(input: &str) -> Result<Card, nom::error::Error<&str>> {
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Vec<Card>, SolveError> {
+    input
+        .lines()
+        .map(|line| parse_card(line).located(input))
+        .collect()
+}
+
+fn parse_card(input: &str) -> Result<Card, nom::error::Error<&str>> {
     fn number_parser(input: &str) -> IResult<&str, u32> {
         preceded(space1, complete::u32)(input)
     }
@@ -104,13 +137,7 @@ fn parse(input: &str) -> Result<Card, nom::error::Error<&str>> {
     final_parser(card_parser)(input)
 }
 
-#[cfg(test)]
-mod tests {
-    use rstest::rstest;
-
-    use super::*;
-
-    const TEST_INPUT: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+const EXAMPLE_INPUT: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
 Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
 Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
@@ -118,9 +145,15 @@ Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
 ";
 
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 13);
     }
 
@@ -132,14 +165,14 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
     #[case("Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36", 0)]
     #[case("Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11", 0)]
     fn score_part1(#[case] card: &str, #[case] expected: u32) {
-        let card = parse(card).unwrap();
+        let card = parse_card(card).unwrap();
         let score = card.score();
         assert_eq!(score, expected);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 30);
     }
 }
@@ -1,5 +1,9 @@
 use std::ops::Range;
 
+use aoc_util::{
+    error::{Located, SolveError},
+    solution::{Example, Solution},
+};
 use itertools::Itertools;
 use nom::{
     bytes::complete::{tag, take_until},
@@ -10,33 +14,51 @@ use nom::{
 };
 use nom_supreme::final_parser::final_parser;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 5;
+    const TITLE: &'static str = "If You Give A Seed A Fertilizer";
+
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 35, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 46, solve_part2)]
+    }
 }
 
-fn solve_part1(input: &str) -> u64 {
-    let almanac = parse(input).unwrap();
+fn solve_part1(input: &str) -> Result<u64, SolveError> {
+    let almanac = parse(input).located(input)?;
 
     almanac
         .seeds
         .iter()
         .map(|&seed| almanac.maps.iter().fold(seed, |acc, map| map.map(acc)))
         .min()
-        .unwrap()
-}
-
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+        .ok_or_else(|| SolveError::unsolvable("No seeds"))
 }
 
-fn solve_part2(input: &str) -> u64 {
-    let almanac = parse(input).unwrap();
+fn solve_part2(input: &str) -> Result<u64, SolveError> {
+    let almanac = parse(input).located(input)?;
 
     almanac
         .seeds
         .iter()
         .tuples()
-        .map(|(&start, &len)| (start..(start + len)))
+        .map(|(&start, &len)| start..(start + len))
         .flat_map(|range| {
             almanac
                 .maps
@@ -45,7 +67,7 @@ fn solve_part2(input: &str) -> u64 {
         })
         .map(|range| range.start)
         .min()
-        .unwrap()
+        .ok_or_else(|| SolveError::unsolvable("No seed ranges"))
 }
 
 #[derive(Debug)]
@@ -188,6 +210,7 @@ enum RangeMap {
     },
 }
 
+#[tracing::instrument(skip_all)]
 fn parse(input: &str) -> Result<Almanac, nom::error::Error<&str>> {
     let seeds_parser = preceded(tag("seeds: "), separated_list1(space1, complete::u64));
 
@@ -212,13 +235,7 @@ fn parse(input: &str) -> Result<Almanac, nom::error::Error<&str>> {
     )
 }
 
-#[cfg(test)]
-mod tests {
-    use rstest::rstest;
-
-    use super::*;
-
-    const TEST_INPUT: &str = "seeds: 79 14 55 13
+const EXAMPLE_INPUT: &str = "seeds: 79 14 55 13
 
 seed-to-soil map:
 50 98 2
@@ -253,21 +270,27 @@ humidity-to-location map:
 56 93 4
 ";
 
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
     #[test]
     fn test_parser() {
-        let solution = parse(TEST_INPUT);
+        let solution = parse(EXAMPLE_INPUT);
         assert!(solution.is_ok());
     }
 
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 35);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 46);
     }
 
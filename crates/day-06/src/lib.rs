@@ -1,3 +1,7 @@
+use aoc_util::{
+    error::{Located, SolveError},
+    solution::{Example, Solution},
+};
 use nom::{
     bytes::complete::tag,
     character::complete::{self, digit1, line_ending, space1},
@@ -7,18 +11,43 @@ use nom::{
 };
 use nom_supreme::final_parser::final_parser;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 6;
+    const TITLE: &'static str = "Wait For It";
+
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 288, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 71503, solve_part2)]
+    }
 }
 
-fn solve_part1(input: &str) -> i64 {
-    let sheet = parse_part1(input).unwrap();
-    sheet
+fn solve_part1(input: &str) -> Result<i64, SolveError> {
+    let sheet = parse_part1(input).located(input)?;
+    let product = sheet
         .iter()
         .map(|(total_time, target_distance)| winning_charge_time_count(total_time, target_distance))
-        .product()
+        .product();
+
+    Ok(product)
 }
 
+#[tracing::instrument(skip_all)]
 fn parse_part1(input: &str) -> Result<Sheet, nom::error::Error<&str>> {
     let line_parser = |prefix| {
         delimited(
@@ -36,18 +65,17 @@ fn parse_part1(input: &str) -> Result<Sheet, nom::error::Error<&str>> {
     )
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> i64 {
-    let sheet = parse_part2(input).unwrap();
-    sheet
+fn solve_part2(input: &str) -> Result<i64, SolveError> {
+    let sheet = parse_part2(input).located(input)?;
+    let product = sheet
         .iter()
         .map(|(total_time, target_distance)| winning_charge_time_count(total_time, target_distance))
-        .product()
+        .product();
+
+    Ok(product)
 }
 
+#[tracing::instrument(skip_all)]
 fn parse_part2(input: &str) -> Result<Sheet, nom::error::Error<&str>> {
     let line_parser = |prefix| {
         delimited(
@@ -120,19 +148,19 @@ impl Sheet {
     }
 }
 
+const EXAMPLE_INPUT: &str = "Time:      7  15   30
+Distance:  9  40  200
+";
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
 
     use super::*;
 
-    const TEST_INPUT: &str = "Time:      7  15   30
-Distance:  9  40  200
-";
-
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 288);
     }
 
@@ -151,7 +179,7 @@ Distance:  9  40  200
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 71503);
     }
 }
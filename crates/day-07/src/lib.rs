@@ -1,6 +1,9 @@
 use std::cmp::Reverse;
 
-use itertools::Itertools;
+use aoc_util::{
+    error::{Located, SolveError},
+    solution::{Example, Solution},
+};
 use nom::{
     character::complete::{self, one_of, space1},
     combinator::map,
@@ -10,43 +13,57 @@ use nom::{
 };
 use nom_supreme::final_parser::final_parser;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
-}
+pub struct Day;
 
-fn solve_part1(input: &str) -> u64 {
-    let mut hands = input.lines().map(|line| parse(line).unwrap()).collect_vec();
+impl Solution for Day {
+    const ID: u32 = 7;
+    const TITLE: &'static str = "Camel Cards";
 
-    hands.sort_by_cached_key(|(hand, _)| {
-        (
-            HandType::from_cards(&hand.cards, Ruleset::Standard),
-            hand.cards,
-        )
-    });
+    type Answer1 = u64;
+    type Answer2 = u64;
 
-    hands
-        .iter()
-        .enumerate()
-        .map(|(idx, (_, bid))| (idx + 1) as u64 * bid)
-        .sum()
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 6440, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 5905, solve_part2)]
+    }
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+fn solve_part1(input: &str) -> Result<u64, SolveError> {
+    let hands = parse(input)?;
+    Ok(total_winnings(hands, Ruleset::Standard))
 }
 
-fn solve_part2(input: &str) -> u64 {
-    let mut hands = input.lines().map(|line| parse(line).unwrap()).collect_vec();
+fn solve_part2(input: &str) -> Result<u64, SolveError> {
+    let hands = parse(input)?;
+    Ok(total_winnings(hands, Ruleset::Joker))
+}
 
+/// Ranks the hands from weakest to strongest and sums their bids multiplied
+/// by their rank.
+#[tracing::instrument(skip_all)]
+fn total_winnings(mut hands: Vec<(Hand, u64)>, ruleset: Ruleset) -> u64 {
     hands.sort_by_cached_key(|(hand, _)| {
         let mut cards = hand.cards;
-        for card in cards.iter_mut() {
-            if card.0 == 'J' {
-                card.0 = ' ';
+        if ruleset == Ruleset::Joker {
+            for card in cards.iter_mut() {
+                if card.0 == 'J' {
+                    card.0 = ' ';
+                }
             }
         }
 
-        (HandType::from_cards(&hand.cards, Ruleset::Joker), cards)
+        (HandType::from_cards(&hand.cards, ruleset), cards)
     });
 
     hands
@@ -158,7 +175,15 @@ impl Ord for Card {
     }
 }
 
-fn parse(line: &str) -> Result<(Hand, u64), nom::error::Error<&str>> {
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Vec<(Hand, u64)>, SolveError> {
+    input
+        .lines()
+        .map(|line| parse_hand(line).located(input))
+        .collect()
+}
+
+fn parse_hand(line: &str) -> Result<(Hand, u64), nom::error::Error<&str>> {
     fn card_parser(input: &str) -> IResult<&str, Card> {
         map(one_of("AKQJT98765432"), Card)(input)
     }
@@ -176,28 +201,28 @@ fn parse(line: &str) -> Result<(Hand, u64), nom::error::Error<&str>> {
     )
 }
 
-#[cfg(test)]
-mod tests {
-    use rstest::rstest;
-
-    use super::*;
-
-    const TEST_INPUT: &str = "32T3K 765
+const EXAMPLE_INPUT: &str = "32T3K 765
 T55J5 684
 KK677 28
 KTJJT 220
 QQQJA 483
 ";
 
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 6440);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 5905);
     }
 
@@ -225,7 +250,7 @@ JJJJ2 41",
         6839
     )]
     fn extras_part2(#[case] input: &str, #[case] expected: u64) {
-        let solution = solve_part2(input);
+        let solution = solve_part2(input).unwrap();
         assert_eq!(solution, expected);
     }
 }
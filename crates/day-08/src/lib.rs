@@ -1,5 +1,10 @@
 use ahash::AHashMap as HashMap;
 
+use aoc_util::{
+    error::{Located, SolveError},
+    solution::{Example, Solution},
+    validation::Check,
+};
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
@@ -10,18 +15,75 @@ use nom::{
 };
 use nom_supreme::final_parser::final_parser;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 8;
+    const TITLE: &'static str = "Haunted Wasteland";
+
+    type Answer1 = u32;
+    type Answer2 = u64;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![
+            Example::new(EXAMPLE_INPUT1, 2, solve_part1),
+            Example::new(EXAMPLE_INPUT2, 6, solve_part1),
+        ]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT3, 6, solve_part2)]
+    }
+
+    fn validate(input: &str) -> Result<Vec<Check>, SolveError> {
+        let (path, network) = parse(input)?;
+
+        let starts = start_nodes(&network).collect_vec();
+        let has_starts = if starts.is_empty() {
+            Err("no node ends in A".to_string())
+        } else {
+            Ok(())
+        };
+
+        let clean_cycles = starts.into_iter().try_for_each(|start| {
+            let name = network.index[start];
+            match z_steps(&path, &network, start, 2)[..] {
+                [first, second] if second == 2 * first => Ok(()),
+                [first, second] => Err(format!(
+                    "{name} reaches nodes ending in Z after {first} and {second} steps"
+                )),
+                [_] => Err(format!("{name} reaches a node ending in Z only once")),
+                _ => Err(format!("{name} never reaches a node ending in Z")),
+            }
+        });
+
+        Ok(vec![
+            Check::new(2, "Some nodes end in A", has_starts),
+            Check::new(
+                2,
+                "Every start reaches a node ending in Z on a cycle as long as the way there",
+                clean_cycles,
+            ),
+        ])
+    }
 }
 
-fn solve_part1(input: &str) -> u32 {
-    let (path, network) = parse(input).unwrap();
+fn solve_part1(input: &str) -> Result<u32, SolveError> {
+    let (path, network) = parse(input)?;
 
     let mut pos = network
         .index
         .iter()
         .position(|&name| name == "AAA")
-        .unwrap();
+        .ok_or_else(|| SolveError::unsolvable("No node AAA"))?;
     let mut steps = 0;
     for (idx, direction) in path.iter().cycle().enumerate() {
         pos = network.adjacency.get(pos).unwrap().get(*direction);
@@ -31,44 +93,56 @@ fn solve_part1(input: &str) -> u32 {
         }
     }
 
-    steps as u32
+    Ok(steps as u32)
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+fn solve_part2(input: &str) -> Result<u64, SolveError> {
+    let (path, network) = parse(input)?;
+
+    // The walks only meet on nodes ending in Z after the least common multiple
+    // of their first steps if each of them repeats the way to its Z node
+    start_nodes(&network)
+        .map(|start| {
+            z_steps(&path, &network, start, 1)
+                .first()
+                .map(|&steps| steps as u64)
+                .ok_or_else(|| {
+                    SolveError::unsolvable(format!(
+                        "{} never reaches a node ending in Z",
+                        network.index[start]
+                    ))
+                })
+        })
+        .reduce(|a, b| Ok(aoc_util::numerics::least_common_multiple(a?, b?)))
+        .unwrap_or_else(|| Err(SolveError::unsolvable("No node ending in A")))
 }
 
-fn solve_part2(input: &str) -> u64 {
-    let (path, network) = parse(input).unwrap();
+fn start_nodes<'a>(network: &'a Network<&str>) -> impl Iterator<Item = usize> + 'a {
+    network.index.iter().positions(|name| name.ends_with('A'))
+}
 
-    network
-        .index
-        .iter()
-        .enumerate()
-        .filter_map(
-            |(idx, name)| {
-                if name.ends_with('A') {
-                    Some(idx)
-                } else {
-                    None
-                }
-            },
-        )
-        .map(|start| {
-            let mut pos = start;
-            let mut steps = 0;
-            for (idx, direction) in path.iter().cycle().enumerate() {
-                pos = network.adjacency.get(pos).unwrap().get(*direction);
-                if network.index[pos].ends_with('Z') {
-                    steps = idx + 1;
-                    break;
-                }
-            }
+/// Steps after which the walk from `start` is on a node ending in Z, up to
+/// `count` of them. The walk is given up once it has been longer without
+/// reaching such a node than it has states.
+fn z_steps(path: &[Direction], network: &Network<&str>, start: usize, count: usize) -> Vec<usize> {
+    let limit = path.len() * network.index.len();
+    let mut steps = Vec::with_capacity(count);
+    let mut pos = start;
+    let mut last = 0;
 
-            steps as u64
-        })
-        .reduce(aoc_util::numerics::least_common_multiple)
-        .unwrap()
+    for (idx, direction) in path.iter().cycle().enumerate() {
+        if steps.len() == count || idx - last > limit {
+            break;
+        }
+
+        pos = network.adjacency[pos].get(*direction);
+        if network.index[pos].ends_with('Z') {
+            steps.push(idx + 1);
+            last = idx + 1;
+        }
+    }
+
+    steps
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -86,29 +160,32 @@ where
     adjacency: Vec<Node<usize>>,
 }
 
-impl<T> FromIterator<(T, Node<T>)> for Network<T>
-where
-    T: Copy + std::hash::Hash + Eq,
-{
-    fn from_iter<I: IntoIterator<Item = (T, Node<T>)>>(iter: I) -> Self {
-        let mut map = HashMap::new();
-        let mut index = Vec::new();
-        let mut nodes = Vec::new();
-        for (idx, (name, node)) in iter.into_iter().enumerate() {
-            map.insert(name, idx);
-            index.push(name);
-            nodes.push(node);
-        }
+impl<'a> Network<&'a str> {
+    fn new(input: &str, nodes: Nodes<'a>) -> Result<Self, SolveError> {
+        let map = nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, (name, _))| (*name, idx))
+            .collect::<HashMap<_, _>>();
+
+        let position = |name: &str| {
+            map.get(name)
+                .copied()
+                .ok_or_else(|| SolveError::at(input, name, "Unknown node"))
+        };
 
         let adjacency = nodes
             .iter()
-            .map(|node| Node {
-                left: *map.get(&node.left).unwrap(), //index.iter().position(|&name| name == node.left).unwrap(),
-                right: *map.get(&node.right).unwrap(), //index.iter().position(|&name| name == node.right).unwrap(),
+            .map(|(_, node)| {
+                Ok(Node {
+                    left: position(node.left)?,
+                    right: position(node.right)?,
+                })
             })
-            .collect_vec();
+            .collect::<Result<Vec<_>, SolveError>>()?;
+        let index = nodes.into_iter().map(|(name, _)| name).collect_vec();
 
-        Self { index, adjacency }
+        Ok(Self { index, adjacency })
     }
 }
 
@@ -133,7 +210,15 @@ where
     }
 }
 
-fn parse(input: &str) -> Result<(Vec<Direction>, Network<&str>), nom::error::Error<&str>> {
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<(Vec<Direction>, Network<&str>), SolveError> {
+    let (path, nodes) = parse_nodes(input).located(input)?;
+    Ok((path, Network::new(input, nodes)?))
+}
+
+type Nodes<'a> = Vec<(&'a str, Node<&'a str>)>;
+
+fn parse_nodes(input: &str) -> Result<(Vec<Direction>, Nodes<'_>), nom::error::Error<&str>> {
     let direction_parser = one_of("LR").map(|c| match c {
         'L' => Direction::Left,
         'R' => Direction::Right,
@@ -153,25 +238,19 @@ fn parse(input: &str) -> Result<(Vec<Direction>, Network<&str>), nom::error::Err
     )
     .map(|(name, (left, right))| (name, Node { left, right }));
 
-    let network_parser = many1(terminated(node_parser, line_ending)).map(Network::from_iter);
+    let network_parser = many1(terminated(node_parser, line_ending));
 
     let parser = separated_pair(path_parser, pair(line_ending, line_ending), network_parser);
 
     final_parser::<
         &str,
-        (Vec<Direction>, Network<&str>),
+        (Vec<Direction>, Nodes<'_>),
         nom::error::Error<&str>,
         nom::error::Error<&str>,
     >(parser)(input)
 }
 
-#[cfg(test)]
-mod tests {
-    use rstest::rstest;
-
-    use super::*;
-
-    const TEST_INPUT1: &str = "RL
+const EXAMPLE_INPUT1: &str = "RL
 
 AAA = (BBB, CCC)
 BBB = (DDD, EEE)
@@ -182,14 +261,14 @@ GGG = (GGG, GGG)
 ZZZ = (ZZZ, ZZZ)
 ";
 
-    const TEST_INPUT2: &str = "LLR
+const EXAMPLE_INPUT2: &str = "LLR
 
 AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)
 ";
 
-    const TEST_INPUT3: &str = "LR
+const EXAMPLE_INPUT3: &str = "LR
 
 11A = (11B, XXX)
 11B = (XXX, 11Z)
@@ -201,18 +280,42 @@ ZZZ = (ZZZ, ZZZ)
 XXX = (XXX, XXX)
 ";
 
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
     #[rstest]
-    #[case(TEST_INPUT1, 2)]
-    #[case(TEST_INPUT2, 6)]
+    #[case(EXAMPLE_INPUT1, 2)]
+    #[case(EXAMPLE_INPUT2, 6)]
     fn test_part1(#[case] input: &str, #[case] expected: u32) {
-        let solution = solve_part1(input);
+        let solution = solve_part1(input).unwrap();
         assert_eq!(solution, expected);
     }
 
     #[rstest]
-    #[case(TEST_INPUT3, 6)]
+    #[case(EXAMPLE_INPUT3, 6)]
     fn test_part2(#[case] input: &str, #[case] expected: u64) {
-        let solution = solve_part2(input);
+        let solution = solve_part2(input).unwrap();
         assert_eq!(solution, expected);
     }
+
+    #[test]
+    fn test_unknown_node() {
+        let input = "L\n\nAAA = (BBB, ZZZ)\nZZZ = (ZZZ, ZZZ)\n";
+        let error = solve_part1(input).unwrap_err();
+        assert_eq!(error.to_string(), "Unknown node at line 3, column 8");
+    }
+
+    #[rstest]
+    #[case(EXAMPLE_INPUT3, None)]
+    #[case(
+        EXAMPLE_INPUT1,
+        Some("AAA reaches nodes ending in Z after 2 and 3 steps")
+    )]
+    fn test_validate(#[case] input: &str, #[case] violation: Option<&str>) {
+        let checks = Day::validate(input).unwrap();
+        assert_eq!(checks[1].violation.as_deref(), violation);
+    }
 }
@@ -1,26 +1,54 @@
+use aoc_util::{
+    error::SolveError,
+    solution::{Example, Solution},
+};
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 9;
+    const TITLE: &'static str = "Mirage Maintenance";
+
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 114, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 2, solve_part2)]
+    }
 }
 
-fn solve_part1(input: &str) -> i64 {
-    input
-        .lines()
-        .map(parse)
-        .map(|history| extrapolate(history.into_iter()))
-        .sum()
+fn solve_part1(input: &str) -> Result<i64, SolveError> {
+    let histories = parse(input)?;
+    Ok(sum_predictions(histories))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+fn solve_part2(input: &str) -> Result<i64, SolveError> {
+    let mut histories = parse(input)?;
+    for history in histories.iter_mut() {
+        history.reverse();
+    }
+    Ok(sum_predictions(histories))
 }
 
-fn solve_part2(input: &str) -> i64 {
-    input
-        .lines()
-        .map(parse)
-        .map(|history| extrapolate(history.into_iter().rev()))
+/// Sums the next value predicted for every history.
+#[tracing::instrument(skip_all)]
+fn sum_predictions(histories: Vec<Vec<i64>>) -> i64 {
+    histories
+        .into_iter()
+        .map(|history| extrapolate(history.into_iter()))
         .sum()
 }
 
@@ -49,30 +77,45 @@ fn delta_sequence(range: &[i64]) -> Vec<i64> {
         .collect_vec()
 }
 
-fn parse(line: &str) -> Vec<i64> {
-    line.split_ascii_whitespace()
-        .map(|e| e.parse().unwrap())
-        .collect_vec()
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Vec<Vec<i64>>, SolveError> {
+    input
+        .lines()
+        .map(|line| parse_history(input, line))
+        .collect()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn parse_history<'a>(input: &'a str, line: &'a str) -> Result<Vec<i64>, SolveError> {
+    if line.trim().is_empty() {
+        return Err(SolveError::at(input, line, "Expected a history"));
+    }
+
+    line.split_ascii_whitespace()
+        .map(|e| {
+            e.parse()
+                .map_err(|_| SolveError::at(input, e, "Expected a number"))
+        })
+        .collect()
+}
 
-    const TEST_INPUT: &str = "0 3 6 9 12 15
+const EXAMPLE_INPUT: &str = "0 3 6 9 12 15
 1 3 6 10 15 21
 10 13 16 21 30 45
 ";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 114);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 2);
     }
 }
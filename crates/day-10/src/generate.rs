@@ -0,0 +1,157 @@
+use std::cmp::Ordering;
+
+use aoc_util::random::Rng;
+
+/// Tiles scattered around the loop
+const JUNK: [char; 7] = ['|', '-', 'L', 'J', '7', 'F', '.'];
+
+/// Generates a `size` by `size` sketch whose loop outlines a random shape,
+/// surrounded by unconnected pipes.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let size = size.max(3);
+    let columns = columns(size, rng);
+    let outline = outline(&columns);
+    render(size, &outline, rng)
+}
+
+/// Cell rows `top..=bottom` covered by every column of a shape whose
+/// neighbouring columns overlap, so its outline is a single simple loop.
+/// Returns the first column and the row ranges.
+fn columns(size: usize, rng: &mut Rng) -> (usize, Vec<(usize, usize)>) {
+    const STEP: usize = 3;
+
+    let cells = size - 1;
+    let first = rng.index(cells / 4 + 1);
+    let last = cells - 1 - rng.index(cells / 4 + 1);
+
+    let top = rng.index(cells);
+    let bottom = top + rng.index(cells - top);
+    let mut columns = vec![(top, bottom)];
+
+    for _ in first..last {
+        let (top, bottom) = *columns.last().unwrap();
+
+        let next_top =
+            rng.range(top.saturating_sub(STEP) as u64..(bottom.min(top + STEP) + 1) as u64);
+        let lowest = top.max(next_top as usize).max(bottom.saturating_sub(STEP));
+        let next_bottom = rng.range(lowest as u64..((cells - 1).min(bottom + STEP) + 1) as u64);
+
+        columns.push((next_top as usize, next_bottom as usize));
+    }
+
+    (first, columns)
+}
+
+/// Corners around the cells of the shape, clockwise from its top left.
+fn outline((first, columns): &(usize, Vec<(usize, usize)>)) -> Vec<(usize, usize)> {
+    fn step_to(outline: &mut Vec<(usize, usize)>, (x, y): (usize, usize)) {
+        while let Some(&(px, py)) = outline.last() {
+            if (px, py) == (x, y) {
+                break;
+            }
+
+            outline.push(match (px.cmp(&x), py.cmp(&y)) {
+                (Ordering::Less, _) => (px + 1, py),
+                (Ordering::Greater, _) => (px - 1, py),
+                (_, Ordering::Less) => (px, py + 1),
+                _ => (px, py - 1),
+            });
+        }
+    }
+
+    let mut outline = vec![(*first, columns[0].0)];
+
+    for (offset, &(top, _)) in columns.iter().enumerate() {
+        let x = first + offset;
+        step_to(&mut outline, (x, top));
+        step_to(&mut outline, (x + 1, top));
+    }
+
+    for (offset, &(_, bottom)) in columns.iter().enumerate().rev() {
+        let x = first + offset;
+        step_to(&mut outline, (x + 1, bottom + 1));
+        step_to(&mut outline, (x, bottom + 1));
+    }
+
+    step_to(&mut outline, (*first, columns[0].0 + 1));
+    outline
+}
+
+fn render(size: usize, outline: &[(usize, usize)], rng: &mut Rng) -> String {
+    let mut tiles = (0..size)
+        .map(|_| (0..size).map(|_| *rng.choose(&JUNK)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    for (index, &(x, y)) in outline.iter().enumerate() {
+        let previous = outline[(index + outline.len() - 1) % outline.len()];
+        let next = outline[(index + 1) % outline.len()];
+
+        let towards = |(tx, ty): (usize, usize)| match (tx.cmp(&x), ty.cmp(&y)) {
+            (Ordering::Less, _) => 'W',
+            (Ordering::Greater, _) => 'E',
+            (_, Ordering::Less) => 'N',
+            _ => 'S',
+        };
+
+        tiles[y][x] = match (towards(previous), towards(next)) {
+            ('N', 'S') | ('S', 'N') => '|',
+            ('W', 'E') | ('E', 'W') => '-',
+            ('N', 'E') | ('E', 'N') => 'L',
+            ('N', 'W') | ('W', 'N') => 'J',
+            ('S', 'W') | ('W', 'S') => '7',
+            _ => 'F',
+        };
+    }
+
+    let (x, y) = *rng.choose(outline);
+    tiles[y][x] = 'S';
+
+    // Only the two loop tiles next to the start may connect to it
+    let on_outline = |position| outline.contains(&position);
+    for (nx, ny) in [
+        (x.wrapping_sub(1), y),
+        (x + 1, y),
+        (x, y.wrapping_sub(1)),
+        (x, y + 1),
+    ] {
+        if nx < size && ny < size && !on_outline((nx, ny)) {
+            tiles[ny][nx] = '.';
+        }
+    }
+
+    tiles
+        .into_iter()
+        .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{solve_part1, solve_part2};
+
+    #[rstest]
+    #[case(3, 1)]
+    #[case(10, 2)]
+    #[case(40, 3)]
+    #[case(100, 4)]
+    fn test_generate(#[case] size: usize, #[case] seed: u64) {
+        let mut rng = Rng::new(seed);
+        let columns = columns(size, &mut rng);
+        let outline = outline(&columns);
+        let input = render(size, &outline, &mut rng);
+
+        // Pick's theorem gives the tiles enclosed by the loop from its area
+        let area = columns
+            .1
+            .iter()
+            .map(|(top, bottom)| bottom - top + 1)
+            .sum::<usize>();
+        let enclosed = area + 1 - outline.len() / 2;
+
+        assert_eq!(solve_part1(&input).unwrap() as usize, outline.len() / 2);
+        assert_eq!(solve_part2(&input).unwrap() as usize, enclosed);
+    }
+}
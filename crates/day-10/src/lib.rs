@@ -1,27 +1,64 @@
 use ahash::AHashSet as HashSet;
 
-use nalgebra::{vector, Vector2};
+use aoc_util::{
+    error::SolveError,
+    grid::Grid,
+    random::Rng,
+    solution::{Example, Solution},
+};
+use nalgebra::Vector2;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+mod generate;
+
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 10;
+    const TITLE: &'static str = "Pipe Maze";
+
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![
+            Example::new(EXAMPLE_INPUT1, 4, solve_part1),
+            Example::new(EXAMPLE_INPUT2, 8, solve_part1),
+        ]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![
+            Example::new(EXAMPLE_INPUT3, 4, solve_part2),
+            Example::new(EXAMPLE_INPUT4, 8, solve_part2),
+            Example::new(EXAMPLE_INPUT5, 10, solve_part2),
+        ]
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Option<String> {
+        Some(generate::generate(size, rng))
+    }
 }
 
-fn solve_part1(input: &str) -> u32 {
-    let sketch = parse(input);
+fn solve_part1(input: &str) -> Result<u32, SolveError> {
+    let sketch = parse(input)?;
 
     let mut steps = 0;
     sketch.visit_path(|_| {
         steps += 1;
-    });
-
-    steps / 2
-}
+    })?;
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+    Ok(steps / 2)
 }
 
-fn solve_part2(input: &str) -> u32 {
+fn solve_part2(input: &str) -> Result<u32, SolveError> {
     #[derive(PartialEq, Eq)]
     enum State {
         Out,
@@ -68,18 +105,21 @@ fn solve_part2(input: &str) -> u32 {
         }
     }
 
-    let sketch = parse(input);
+    let sketch = parse(input)?;
     let mut path = HashSet::new();
     sketch.visit_path(|idx| {
         path.insert(idx);
-    });
+    })?;
 
     let mut counter = 0;
-    for y in 0..sketch.height {
+    for y in 0..sketch.tiles.height() {
         let mut state = State::Out;
 
-        for x in 0..sketch.width {
-            let idx = sketch.index_of(Pos::new(x as i64, y as i64));
+        for x in 0..sketch.tiles.width() {
+            let idx = sketch
+                .tiles
+                .index(&Pos::new(x, y))
+                .expect("Position within the sketch");
             let on_edge = path.contains(&idx);
 
             if on_edge {
@@ -90,81 +130,70 @@ fn solve_part2(input: &str) -> u32 {
         }
     }
 
-    counter
+    Ok(counter)
 }
 
 type Pos = Vector2<i64>;
 
 struct Sketch {
-    width: usize,
-    height: usize,
     start: usize,
-    tiles: Vec<Direction>,
+    tiles: Grid<Direction>,
 }
 
 impl Sketch {
-    pub fn new(width: usize, height: usize, start: Pos, tiles: Vec<Direction>) -> Self {
-        assert_eq!(width * height, tiles.len());
-        assert!(start.x >= 0 && (start.x as usize) < width);
-        assert!(start.y >= 0 && (start.y as usize) < height);
-
-        Self {
-            width,
-            height,
-            start: start.y as usize * width + start.x as usize,
-            tiles,
-        }
-    }
+    pub fn new(tiles: Grid<Direction>, start: Pos) -> Self {
+        let start = tiles.index(&start).expect("Start outside the sketch");
 
-    pub fn index_of(&self, position: Pos) -> usize {
-        assert!(position.x >= 0 && (position.x as usize) < self.width);
-        assert!(position.y >= 0 && (position.y as usize) < self.height);
-        position.y as usize * self.width + position.x as usize
+        Self { start, tiles }
     }
 
-    pub fn visit_path(&self, mut visit: impl FnMut(usize)) {
+    /// Visits the tiles of the loop through the start tile, ending with the
+    /// start tile. Fails if the pipes leaving the start tile do not lead back
+    /// to it.
+    #[tracing::instrument(skip_all)]
+    pub fn visit_path(&self, mut visit: impl FnMut(usize)) -> Result<(), SolveError> {
+        let broken = || SolveError::unsolvable("The loop through the start tile is broken");
+
         let mut previous = self.start;
-        let mut current = self.neighbors(self.start).next().unwrap();
+        let mut current = self
+            .neighbors(self.start)
+            .next()
+            .ok_or_else(|| SolveError::unsolvable("No pipe connects to the start tile"))?;
 
         loop {
             visit(current);
 
             if current == self.start {
-                break;
+                return Ok(());
+            }
+
+            // A pipe that does not point back is not connected
+            if !self.neighbors(current).any(|next| next == previous) {
+                return Err(broken());
             }
 
             let next = self
                 .neighbors(current)
                 .find(|&next| next != previous)
-                .unwrap();
+                .ok_or_else(broken)?;
             previous = current;
             current = next;
         }
     }
 
     fn start_direction(&self) -> Direction {
-        let mut direction = Direction::empty();
-
-        let x = self.start % self.width;
-        let y = self.start / self.width;
-
-        if y > 0 && self.tiles[self.start - self.width].contains(Direction::South) {
-            direction |= Direction::North;
-        }
-
-        if y + 1 < self.height && self.tiles[self.start + self.width].contains(Direction::North) {
-            direction |= Direction::South;
-        }
-
-        if x > 0 && self.tiles[self.start - 1].contains(Direction::East) {
-            direction |= Direction::West
-        }
-
-        if x + 1 < self.width && self.tiles[self.start + 1].contains(Direction::West) {
-            direction |= Direction::East
-        }
-
-        direction
+        let position = self.tiles.position(self.start);
+
+        STEPS
+            .into_iter()
+            .filter(|&(_, back, step)| {
+                self.tiles
+                    .get(&(position + Pos::from(step)))
+                    .is_some_and(|tile| tile.contains(back))
+            })
+            .fold(Direction::empty(), |direction, (pipe, _, _)| {
+                direction | pipe
+            })
     }
 
     fn tile(&self, idx: usize) -> Direction {
@@ -175,38 +204,26 @@ impl Sketch {
         }
     }
 
-    fn neighbors(&self, idx: usize) -> impl Iterator<Item = usize> {
-        let mut neighbors = [0; 4];
-        let mut count = 0;
-
-        let x = idx % self.width;
-        let y = idx / self.width;
-
+    fn neighbors(&self, idx: usize) -> impl Iterator<Item = usize> + '_ {
+        let position = self.tiles.position(idx);
         let direction = self.tile(idx);
-        if direction.contains(Direction::North) && y > 0 {
-            neighbors[count] = idx - self.width;
-            count += 1;
-        }
-
-        if direction.contains(Direction::South) && y + 1 < self.height {
-            neighbors[count] = idx + self.width;
-            count += 1;
-        }
-
-        if direction.contains(Direction::West) && x > 0 {
-            neighbors[count] = idx - 1;
-            count += 1;
-        }
 
-        if direction.contains(Direction::East) && x + 1 < self.width {
-            neighbors[count] = idx + 1;
-            count += 1;
-        }
-
-        neighbors.into_iter().take(count)
+        STEPS
+            .into_iter()
+            .filter(move |&(pipe, _, _)| direction.contains(pipe))
+            .filter_map(move |(_, _, step)| self.tiles.index(&(position + Pos::from(step))))
     }
 }
 
+/// Every pipe end, with the end of the neighboring pipe that connects to it
+/// and the step to that neighbor.
+const STEPS: [(Direction, Direction, [i64; 2]); 4] = [
+    (Direction::North, Direction::South, [0, -1]),
+    (Direction::South, Direction::North, [0, 1]),
+    (Direction::West, Direction::East, [-1, 0]),
+    (Direction::East, Direction::West, [1, 0]),
+];
+
 bitflags::bitflags! {
     #[derive(Clone, Copy)]
     struct Direction: u8 {
@@ -231,48 +248,32 @@ impl From<char> for Direction {
     }
 }
 
-fn parse(input: &str) -> Sketch {
-    let mut start = Pos::zeros();
-    let mut width = 0;
-    let mut height = 0;
-    let mut tiles = Vec::new();
-
-    for (y, line) in input.lines().enumerate() {
-        height = height.max(y + 1);
-        for (x, tile) in line.chars().enumerate() {
-            width = width.max(x + 1);
-            tiles.push(Direction::from(tile));
-
-            if tile == 'S' {
-                start = vector![x as i64, y as i64];
-            }
-        }
-    }
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Sketch, SolveError> {
+    let (tiles, starts) =
+        Grid::parse_marked(input, 'S', |tile, _| Ok::<_, String>(Direction::from(tile)))?;
 
-    Sketch::new(width, height, start, tiles)
+    let start = starts
+        .first()
+        .ok_or_else(|| SolveError::unsolvable("No start tile"))?;
+    Ok(Sketch::new(tiles, *start))
 }
 
-#[cfg(test)]
-mod tests {
-    use rstest::rstest;
-
-    use super::*;
-
-    const TEST_INPUT1: &str = "-L|F7
+const EXAMPLE_INPUT1: &str = "-L|F7
 7S-7|
 L|7||
 -L-J|
 L|-JF
 ";
 
-    const TEST_INPUT2: &str = "7-F7-
+const EXAMPLE_INPUT2: &str = "7-F7-
 .FJ|7
 SJLL7
 |F--J
 LJ.LJ
 ";
 
-    const TEST_INPUT3: &str = "...........
+const EXAMPLE_INPUT3: &str = "...........
 .S-------7.
 .|F-----7|.
 .||.....||.
@@ -283,7 +284,7 @@ LJ.LJ
 ...........
 ";
 
-    const TEST_INPUT4: &str = ".F----7F7F7F7F-7....
+const EXAMPLE_INPUT4: &str = ".F----7F7F7F7F-7....
 .|F--7||||||||FJ....
 .||.FJ||||||||L7....
 FJL7L7LJLJ||LJ.L-7..
@@ -295,7 +296,7 @@ L--J.L7...LJS7F-7L7.
 ....L---J.LJ.LJLJ...
 ";
 
-    const TEST_INPUT5: &str = "FF7FSF7F7F7F7F7F---7
+const EXAMPLE_INPUT5: &str = "FF7FSF7F7F7F7F7F---7
 L|LJ||||||||||||F--J
 FL-7LJLJ||||||LJL-77
 F--JF--7||LJLJ7F7FJ-
@@ -307,20 +308,37 @@ L.L7LFJ|||||FJL7||LJ
 L7JLJL-JLJLJL--JLJ.L
 ";
 
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
     #[rstest]
-    #[case(TEST_INPUT1, 4)]
-    #[case(TEST_INPUT2, 8)]
+    #[case(EXAMPLE_INPUT1, 4)]
+    #[case(EXAMPLE_INPUT2, 8)]
     fn test_part1(#[case] input: &str, #[case] expected: u32) {
-        let solution = solve_part1(input);
+        let solution = solve_part1(input).unwrap();
         assert_eq!(solution, expected);
     }
 
     #[rstest]
-    #[case(TEST_INPUT3, 4)]
-    #[case(TEST_INPUT4, 8)]
-    #[case(TEST_INPUT5, 10)]
+    #[case(EXAMPLE_INPUT3, 4)]
+    #[case(EXAMPLE_INPUT4, 8)]
+    #[case(EXAMPLE_INPUT5, 10)]
     fn test_part2(#[case] input: &str, #[case] expected: u32) {
-        let solution = solve_part2(input);
+        let solution = solve_part2(input).unwrap();
         assert_eq!(solution, expected);
     }
+
+    #[rstest]
+    #[case("...\n.S.\n...\n", "No pipe connects to the start tile")]
+    #[case(
+        ".....\n.S-7.\n.|.-.\n.L-J.\n.....\n",
+        "The loop through the start tile is broken"
+    )]
+    fn test_broken_loop(#[case] input: &str, #[case] message: &str) {
+        let error = solve_part1(input).unwrap_err();
+        assert_eq!(error.to_string(), message);
+    }
 }
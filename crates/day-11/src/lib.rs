@@ -1,35 +1,68 @@
 use ahash::AHashSet as HashSet;
 
-use aoc_util::geometry::manhattan_distance;
+use aoc_util::{
+    error::SolveError,
+    geometry::manhattan_distance,
+    solution::{Example, Solution},
+};
 use itertools::Itertools;
 use nalgebra::Vector2;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 11;
+    const TITLE: &'static str = "Cosmic Expansion";
+
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input, 1_000_000)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 374, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![
+            Example::new(EXAMPLE_INPUT, 1030, |input| solve_part2(input, 10))
+                .with_parameters(&[("expansion", 10)]),
+            Example::new(EXAMPLE_INPUT, 8410, |input| solve_part2(input, 100))
+                .with_parameters(&[("expansion", 100)]),
+        ]
+    }
 }
 
-fn solve_part1(input: &str) -> u64 {
-    let mut map = parse(input);
+fn solve_part1(input: &str) -> Result<u64, SolveError> {
+    let mut map = parse(input)?;
     map.expand(2);
-    map.galaxies
+    let sum = map
+        .galaxies
         .iter()
         .tuple_combinations()
         .map(|(a, b)| manhattan_distance(*a, *b) as u64)
-        .sum()
-}
+        .sum();
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input, 1_000_000)
+    Ok(sum)
 }
 
-fn solve_part2(input: &str, expansion: i64) -> u64 {
-    let mut map = parse(input);
+fn solve_part2(input: &str, expansion: i64) -> Result<u64, SolveError> {
+    let mut map = parse(input)?;
     map.expand(expansion);
-    map.galaxies
+    let sum = map
+        .galaxies
         .iter()
         .tuple_combinations()
         .map(|(a, b)| manhattan_distance(*a, *b) as u64)
-        .sum()
+        .sum();
+
+    Ok(sum)
 }
 
 type Pos = Vector2<i64>;
@@ -42,6 +75,7 @@ struct Map {
 }
 
 impl Map {
+    #[tracing::instrument(skip_all)]
     pub fn expand(&mut self, mut expansion: i64) {
         expansion = (expansion - 1).max(0);
 
@@ -54,7 +88,8 @@ impl Map {
     }
 }
 
-fn parse(input: &str) -> Map {
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Map, SolveError> {
     let mut galaxies = vec![];
     let mut empty_rows = Vec::new();
     let mut columns = HashSet::new();
@@ -63,6 +98,15 @@ fn parse(input: &str) -> Map {
     for (y, line) in input.lines().enumerate() {
         let y = y as i64;
         let mut empty = true;
+
+        if let Some(offset) = line.find(|c| c != '.' && c != '#') {
+            return Err(SolveError::at(
+                input,
+                &line[offset..],
+                "Expected `.` or `#`",
+            ));
+        }
+
         for (x, _) in line.chars().enumerate().filter(|&(_, c)| c == '#') {
             let x = x as i64;
             max_column = max_column.max(x);
@@ -81,20 +125,14 @@ fn parse(input: &str) -> Map {
         .filter(|col| !columns.contains(col))
         .collect_vec();
 
-    Map {
+    Ok(Map {
         galaxies,
         empty_rows,
         empty_columns,
-    }
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use rstest::rstest;
-
-    use super::*;
-
-    const TEST_INPUT: &str = "...#......
+const EXAMPLE_INPUT: &str = "...#......
 .......#..
 #.........
 ..........
@@ -106,9 +144,15 @@ mod tests {
 #...#.....
 ";
 
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 374);
     }
 
@@ -116,7 +160,7 @@ mod tests {
     #[case(10, 1030)]
     #[case(100, 8410)]
     fn test_part2(#[case] expansion: i64, #[case] expected: u64) {
-        let solution = solve_part2(TEST_INPUT, expansion);
+        let solution = solve_part2(EXAMPLE_INPUT, expansion).unwrap();
         assert_eq!(solution, expected);
     }
 }
@@ -0,0 +1,99 @@
+use aoc_util::random::Rng;
+
+/// Generates `size` rows of springs, each hiding a random arrangement behind
+/// unknown springs.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size.max(1))
+        .map(|_| {
+            let mut row = row(rng);
+            row.push('\n');
+            row
+        })
+        .collect()
+}
+
+fn row(rng: &mut Rng) -> String {
+    let length = rng.range(4..17) as usize;
+
+    let mut springs = (0..length)
+        .map(|_| if rng.chance(0.4) { '#' } else { '.' })
+        .collect::<Vec<_>>();
+    // The pattern must not be empty
+    springs[rng.index(length)] = '#';
+
+    let groups = springs
+        .split(|&spring| spring == '.')
+        .filter(|group| !group.is_empty())
+        .map(|group| group.len().to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    for spring in &mut springs {
+        if rng.chance(0.5) {
+            *spring = '?';
+        }
+    }
+
+    format!("{} {groups}", springs.into_iter().collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_util::cache::NoCache;
+
+    use super::*;
+    use crate::{count_fits, parse_row, SpringState};
+
+    /// Counts the arrangements by trying every assignment of the unknowns.
+    fn brute_force(springs: &[SpringState], pattern: &[usize]) -> usize {
+        let unknown = springs
+            .iter()
+            .filter(|&&spring| spring == SpringState::Unknown)
+            .count();
+
+        (0..1u32 << unknown)
+            .filter(|assignment| {
+                let mut bit = 0;
+                let mut groups = Vec::new();
+                let mut run = 0;
+
+                for &spring in springs {
+                    let damaged = match spring {
+                        SpringState::Damaged => true,
+                        SpringState::Operational => false,
+                        SpringState::Unknown => {
+                            bit += 1;
+                            assignment & (1 << (bit - 1)) != 0
+                        }
+                    };
+
+                    if damaged {
+                        run += 1;
+                    } else if run > 0 {
+                        groups.push(run);
+                        run = 0;
+                    }
+                }
+
+                if run > 0 {
+                    groups.push(run);
+                }
+
+                groups == pattern
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(50, &mut Rng::new(12));
+
+        for line in input.lines() {
+            let (springs, pattern) = parse_row(&input, line).unwrap();
+            let fits = count_fits(&springs, &pattern, &mut NoCache);
+
+            assert!(fits >= 1, "{line}");
+            assert_eq!(fits, brute_force(&springs, &pattern), "{line}");
+        }
+    }
+}
@@ -1,40 +1,72 @@
 use ahash::AHashMap as HashMap;
 
-use aoc_util::cache::{Cache, NoCache};
+use aoc_util::{
+    cache::{Cache, NoCache},
+    error::SolveError,
+    random::Rng,
+    solution::{Example, Solution},
+};
 use itertools::Itertools;
 use rayon::prelude::*;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
-}
+mod generate;
 
-fn solve_part1(input: &str) -> usize {
-    input
-        .par_lines()
-        .map(parse)
-        .map(|(springs, pattern)| {
-            let mut cache = NoCache;
-            count_fits(&springs, &pattern, &mut cache)
-        })
-        .sum()
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 12;
+    const TITLE: &'static str = "Hot Springs";
+
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 21, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 525152, solve_part2)]
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Option<String> {
+        Some(generate::generate(size, rng))
+    }
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+fn solve_part1(input: &str) -> Result<usize, SolveError> {
+    let rows = parse(input)?;
+    Ok(count_arrangements(rows, |(springs, pattern)| {
+        let mut cache = NoCache;
+        count_fits(&springs, &pattern, &mut cache)
+    }))
 }
 
 const PART2_EXPANSION: usize = 5;
 
-fn solve_part2(input: &str) -> usize {
-    input
-        .par_lines()
-        .map(parse)
-        .map(|item| expand(item, PART2_EXPANSION))
-        .map(|(springs, pattern)| {
-            let mut cache = HashMap::new();
-            count_fits(&springs, &pattern, &mut cache)
-        })
-        .sum()
+fn solve_part2(input: &str) -> Result<usize, SolveError> {
+    let rows = parse(input)?;
+    Ok(count_arrangements(rows, |row| {
+        let (springs, pattern) = expand(row, PART2_EXPANSION);
+        let mut cache = HashMap::new();
+        count_fits(&springs, &pattern, &mut cache)
+    }))
+}
+
+/// Springs of a row together with the sizes of its damaged groups.
+type Row = (Vec<SpringState>, Vec<usize>);
+
+/// Sums the arrangements of every row, counting the rows in parallel.
+#[tracing::instrument(skip_all)]
+fn count_arrangements(rows: Vec<Row>, count: impl Fn(Row) -> usize + Sync + Send) -> usize {
+    rows.into_par_iter().map(count).sum()
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -44,28 +76,45 @@ enum SpringState {
     Unknown,
 }
 
-fn parse(line: &str) -> (Vec<SpringState>, Vec<usize>) {
-    let (springs, pattern) = line.split_once(' ').unwrap();
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Vec<Row>, SolveError> {
+    input
+        .par_lines()
+        .map(|line| parse_row(input, line))
+        .collect()
+}
+
+fn parse_row<'a>(input: &'a str, line: &'a str) -> Result<Row, SolveError> {
+    let (springs, pattern) = line
+        .split_once(' ')
+        .ok_or_else(|| SolveError::at(input, line, "Expected springs and a pattern"))?;
 
     let springs = springs
-        .chars()
-        .map(|c| match c {
-            '.' => SpringState::Operational,
-            '#' => SpringState::Damaged,
-            '?' => SpringState::Unknown,
-            _ => unreachable!(),
+        .char_indices()
+        .map(|(idx, c)| match c {
+            '.' => Ok(SpringState::Operational),
+            '#' => Ok(SpringState::Damaged),
+            '?' => Ok(SpringState::Unknown),
+            _ => Err(SolveError::at(
+                input,
+                &springs[idx..],
+                "Expected `.`, `#` or `?`",
+            )),
         })
-        .collect_vec();
+        .try_collect()?;
 
-    let pattern = pattern.split(',').map(|s| s.parse().unwrap()).collect_vec();
+    let pattern = pattern
+        .split(',')
+        .map(|s| {
+            s.parse()
+                .map_err(|_| SolveError::at(input, s, "Expected a number"))
+        })
+        .try_collect()?;
 
-    (springs, pattern)
+    Ok((springs, pattern))
 }
 
-fn expand(
-    (springs, pattern): (Vec<SpringState>, Vec<usize>),
-    n: usize,
-) -> (Vec<SpringState>, Vec<usize>) {
+fn expand((springs, pattern): Row, n: usize) -> Row {
     let mut expanded_springs = Vec::with_capacity(springs.len() * n + n - 1);
     for i in 0..n {
         if i > 0 {
@@ -150,13 +199,7 @@ fn slice_from<T>(slice: &[T], idx: usize) -> &[T] {
     &slice[idx..]
 }
 
-#[cfg(test)]
-mod tests {
-    use rstest::rstest;
-
-    use super::*;
-
-    const TEST_INPUT: &str = "???.### 1,1,3
+const EXAMPLE_INPUT: &str = "???.### 1,1,3
 .??..??...?##. 1,1,3
 ?#?#?#?#?#?#?#? 1,3,1,6
 ????.#...#... 4,1,1
@@ -164,9 +207,15 @@ mod tests {
 ?###???????? 3,2,1
 ";
 
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 21);
     }
 
@@ -188,7 +237,7 @@ mod tests {
     #[case("#?#. 3", true)]
     #[case("#?#. 4", false)]
     fn test_can_fit_length(#[case] input: &str, #[case] expected: bool) {
-        let (springs, pattern) = parse(input);
+        let (springs, pattern) = parse_row(input, input).unwrap();
         let length = pattern[0];
         let solution = can_fit_length(&springs, 0, length);
         assert_eq!(solution, expected);
@@ -202,7 +251,7 @@ mod tests {
     #[case("????.######..#####. 1,6,5", 4)]
     #[case("?###???????? 3,2,1", 10)]
     fn test_part1_single(#[case] line: &str, #[case] expected: usize) {
-        let (springs, pattern) = parse(line);
+        let (springs, pattern) = parse_row(line, line).unwrap();
         let mut cache = NoCache;
         let solution = count_fits(&springs, &pattern, &mut cache);
         assert_eq!(solution, expected);
@@ -210,7 +259,7 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 525152);
     }
 
@@ -222,7 +271,7 @@ mod tests {
     #[case("????.######..#####. 1,6,5", 2500)]
     #[case("?###???????? 3,2,1", 506250)]
     fn test_part2_single(#[case] line: &str, #[case] expected: usize) {
-        let (springs, pattern) = expand(parse(line), PART2_EXPANSION);
+        let (springs, pattern) = expand(parse_row(line, line).unwrap(), PART2_EXPANSION);
         let mut cache = HashMap::new();
         let solution = count_fits(&springs, &pattern, &mut cache);
         assert_eq!(solution, expected);
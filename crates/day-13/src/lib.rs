@@ -1,13 +1,40 @@
+use aoc_util::{
+    error::SolveError,
+    grid::Grid,
+    solution::{Example, Solution},
+};
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 13;
+    const TITLE: &'static str = "Point of Incidence";
+
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 405, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 400, solve_part2)]
+    }
 }
 
-fn solve_part1(input: &str) -> usize {
-    let patterns = parse(input);
+fn solve_part1(input: &str) -> Result<usize, SolveError> {
+    let patterns = parse(input)?;
 
-    patterns
+    let sum = patterns
         .iter()
         .filter_map(|pattern| {
             (1..pattern.height)
@@ -15,17 +42,15 @@ fn solve_part1(input: &str) -> usize {
                 .map(|row| row * 100)
                 .or_else(|| (1..pattern.width).find(|column| pattern.folds_at_column(*column)))
         })
-        .sum()
-}
+        .sum();
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+    Ok(sum)
 }
 
-fn solve_part2(input: &str) -> usize {
-    let patterns = parse(input);
+fn solve_part2(input: &str) -> Result<usize, SolveError> {
+    let patterns = parse(input)?;
 
-    patterns
+    let sum = patterns
         .iter()
         .filter_map(|pattern| {
             (1..pattern.height)
@@ -35,132 +60,88 @@ fn solve_part2(input: &str) -> usize {
                     (1..pattern.width).find(|column| pattern.fold_at_column_error(*column) == 1)
                 })
         })
-        .sum()
+        .sum();
+
+    Ok(sum)
 }
 
 struct Pattern {
     width: usize,
     height: usize,
     rows: Vec<u64>,
+    columns: Vec<u64>,
 }
 
 impl Pattern {
-    pub fn folds_at_row(&self, row: usize) -> bool {
-        self.accumulate_row_fold(row, true, |acc, (a, b)| acc && a == b)
-            .unwrap_or(false)
+    /// Packs the rows and columns of `grid`, where rocks are set, into bits.
+    pub fn new(grid: &Grid<bool>) -> Self {
+        let pack = |line: &[bool]| line.iter().fold(0, |acc, &rock| acc << 1 | u64::from(rock));
+
+        Self {
+            width: grid.width() as usize,
+            height: grid.height() as usize,
+            rows: grid.rows().map(pack).collect(),
+            columns: grid.transposed().rows().map(pack).collect(),
+        }
     }
 
-    pub fn fold_at_row_error(&self, row: usize) -> usize {
-        self.accumulate_row_fold(row, 0, |acc, (a, b)| {
-            let error = a ^ b;
-            acc + error.count_ones() as usize
-        })
-        .unwrap_or(usize::MAX)
+    pub fn folds_at_row(&self, row: usize) -> bool {
+        fold_error(&self.rows, row) == Some(0)
     }
 
-    fn accumulate_row_fold<T>(
-        &self,
-        row: usize,
-        init: T,
-        f: impl Fn(T, (u64, u64)) -> T,
-    ) -> Option<T> {
-        if row < 1 || row >= self.height {
-            return None;
-        }
-
-        let checks = row.min(self.height - row);
-        let result = (0..checks)
-            .map(|check| (row - check - 1, row + check))
-            .map(|(a, b)| (self.rows[a], self.rows[b]))
-            .fold(init, f);
-        Some(result)
+    pub fn fold_at_row_error(&self, row: usize) -> usize {
+        fold_error(&self.rows, row).unwrap_or(usize::MAX)
     }
 
     pub fn folds_at_column(&self, column: usize) -> bool {
-        self.accumulate_column_fold(column, true, |acc, (a, b)| acc && a == b)
-            .unwrap_or(false)
+        fold_error(&self.columns, column) == Some(0)
     }
 
     pub fn fold_at_column_error(&self, column: usize) -> usize {
-        self.accumulate_column_fold(column, 0, |acc, (a, b)| {
-            let error = a ^ b;
-            acc + error.count_ones() as usize
-        })
-        .unwrap_or(usize::MAX)
+        fold_error(&self.columns, column).unwrap_or(usize::MAX)
     }
+}
 
-    fn accumulate_column_fold<T>(
-        &self,
-        column: usize,
-        init: T,
-        f: impl Fn(T, (u64, u64)) -> T,
-    ) -> Option<T> {
-        if column < 1 || column >= self.width {
-            return None;
-        }
-
-        let column = self.width - column;
-
-        let checks = column.min(self.width - column);
-        let result = (0..checks)
-            .map(|check| (column - check - 1, column + check))
-            .map(|(a, b)| (self.column(a), self.column(b)))
-            .fold(init, f);
-        Some(result)
+/// Number of tiles that differ from their mirror image when folding `lines`
+/// between `fold - 1` and `fold`.
+fn fold_error(lines: &[u64], fold: usize) -> Option<usize> {
+    if fold < 1 || fold >= lines.len() {
+        return None;
     }
 
-    fn column(&self, index: usize) -> u64 {
-        assert!(index < self.width);
-        self.rows
-            .iter()
-            .fold(0, |acc, row| acc << 1 | ((row >> (index)) & 1))
-    }
+    let checks = fold.min(lines.len() - fold);
+    let error = (0..checks)
+        .map(|check| (lines[fold - check - 1] ^ lines[fold + check]).count_ones() as usize)
+        .sum();
+    Some(error)
 }
 
-fn parse(input: &str) -> Vec<Pattern> {
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Vec<Pattern>, SolveError> {
     input
         .lines()
         .group_by(|line| line.is_empty())
         .into_iter()
         .filter_map(|(empty, group)| (!empty).then_some(group))
-        .map(|group| parse_one(group))
-        .collect_vec()
+        .map(|group| parse_one(input, group))
+        .collect()
 }
 
-fn parse_one<'a>(input: impl Iterator<Item = &'a str>) -> Pattern {
-    let mut width = 0;
-    let mut height = 0;
-    let rows = input
-        .enumerate()
-        .map(|(idx, line)| {
-            assert!(idx < 64);
-            assert!(line.len() <= 64);
-            height += 1;
-
-            if idx == 0 {
-                width = line.len();
-            }
-
-            line.chars().fold(0u64, |acc, c| match c {
-                '.' => acc << 1,
-                '#' => (acc << 1) | 1,
-                _ => unreachable!(),
-            })
-        })
-        .collect_vec();
+fn parse_one<'a>(input: &str, lines: impl Iterator<Item = &'a str>) -> Result<Pattern, SolveError> {
+    let grid = Grid::parse_lines(input, lines, |c, _| match c {
+        '.' => Ok(false),
+        '#' => Ok(true),
+        _ => Err("Expected `.` or `#`"),
+    })?;
 
-    Pattern {
-        width,
-        height,
-        rows,
+    if grid.width() > 64 || grid.height() > 64 {
+        return Err(SolveError::unsolvable("Patterns are at most 64 by 64"));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    Ok(Pattern::new(&grid))
+}
 
-    const TEST_INPUT: &str = "#.##..##.
+const EXAMPLE_INPUT: &str = "#.##..##.
 ..#.##.#.
 ##......#
 ##......#
@@ -177,15 +158,19 @@ mod tests {
 #....#..#
 ";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 405);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 400);
     }
 }
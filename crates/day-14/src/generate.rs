@@ -0,0 +1,50 @@
+use aoc_util::random::Rng;
+
+/// Generates a `size` by `size` platform with randomly scattered cube and
+/// round rocks.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let size = size.max(1);
+
+    let mut platform = String::with_capacity(size * (size + 1));
+    for _ in 0..size {
+        for _ in 0..size {
+            platform.push(match rng.index(10) {
+                0 | 1 => '#',
+                2..=4 => 'O',
+                _ => '.',
+            });
+        }
+        platform.push('\n');
+    }
+
+    platform
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_util::grid::{Direction, Position};
+
+    use super::*;
+    use crate::{parse, solve_part2, Tile};
+
+    #[test]
+    fn test_generate() {
+        let input = generate(30, &mut Rng::new(14));
+        let mut platform = parse(&input).unwrap();
+        let boulders = platform.boulders().count();
+
+        platform.tilt(Direction::Up);
+        assert_eq!(platform.boulders().count(), boulders);
+        assert!(platform
+            .boulders()
+            .map(|idx| platform.field.position(idx) - Position::new(0, 1))
+            .all(|above| platform.field.get(&above) != Some(&Tile::Empty)));
+
+        for _ in 0..10 {
+            platform.cycle();
+            assert_eq!(platform.boulders().count(), boulders);
+        }
+
+        assert!(solve_part2(&input).is_ok());
+    }
+}
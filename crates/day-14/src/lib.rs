@@ -1,23 +1,53 @@
-use std::{collections::BTreeMap, ops::Range};
-
+use std::collections::BTreeMap;
+
+use aoc_util::{
+    error::SolveError,
+    grid::{Direction, Grid},
+    random::Rng,
+    solution::{Example, Solution},
+};
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
-}
+mod generate;
+
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 14;
+    const TITLE: &'static str = "Parabolic Reflector Dish";
+
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 136, solve_part1)]
+    }
 
-fn solve_part1(input: &str) -> usize {
-    let mut platform = parse(input);
-    platform.tilt(Direction::North);
-    platform.load()
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 64, solve_part2)]
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Option<String> {
+        Some(generate::generate(size, rng))
+    }
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+fn solve_part1(input: &str) -> Result<usize, SolveError> {
+    let mut platform = parse(input)?;
+    platform.tilt(Direction::Up);
+    Ok(platform.load())
 }
 
-fn solve_part2(input: &str) -> usize {
-    let mut platform = parse(input);
+fn solve_part2(input: &str) -> Result<usize, SolveError> {
+    let mut platform = parse(input)?;
 
     let mut loads = Vec::new();
     let (cycle_length, cycle_offset) = aoc_util::sequence::detect_cycle_cached(
@@ -31,77 +61,27 @@ fn solve_part2(input: &str) -> usize {
     );
 
     let idx = cycle_offset + (1_000_000_000 - cycle_offset) % cycle_length;
-    loads[idx]
+    Ok(loads[idx])
 }
 
 struct Platform {
-    width: usize,
-    height: usize,
-    field: Vec<Tile>,
+    field: Grid<Tile>,
 }
 
 impl Platform {
     pub fn cycle(&mut self) {
-        self.tilt(Direction::North);
-        self.tilt(Direction::West);
-        self.tilt(Direction::South);
-        self.tilt(Direction::East);
+        self.tilt(Direction::Up);
+        self.tilt(Direction::Left);
+        self.tilt(Direction::Down);
+        self.tilt(Direction::Right);
     }
 
     pub fn tilt(&mut self, direction: Direction) {
-        let width = self.width;
-        let height = self.height;
-
-        match direction {
-            Direction::North => self.apply_tilt(
-                |column: usize, row: usize| row * width + column,
-                0..width,
-                0..height,
-            ),
-            Direction::South => self.apply_tilt(
-                |column: usize, row: usize| (height - row - 1) * width + column,
-                0..width,
-                0..height,
-            ),
-            Direction::West => self.apply_tilt(
-                |column: usize, row: usize| column * width + row,
-                0..height,
-                0..width,
-            ),
-            Direction::East => self.apply_tilt(
-                |column: usize, row: usize| column * width + (width - row - 1),
-                0..height,
-                0..width,
-            ),
-        };
-    }
-
-    fn apply_tilt(
-        &mut self,
-        index: impl Fn(usize, usize) -> usize,
-        column_range: Range<usize>,
-        row_range: Range<usize>,
-    ) {
-        for column in column_range {
-            let mut drop_row = 0;
-            for row in row_range.clone() {
-                let idx = index(column, row);
-                match self.field[idx] {
-                    Tile::Block => {
-                        drop_row = row + 1;
-                    }
-                    Tile::Boulder => {
-                        if row != drop_row {
-                            self.field[index(column, drop_row)] = Tile::Boulder;
-                            self.field[idx] = Tile::Empty;
-                        }
-
-                        drop_row += 1;
-                    }
-                    _ => {}
-                }
-            }
-        }
+        self.field.compact(
+            direction,
+            |tile| *tile == Tile::Boulder,
+            |tile| *tile == Tile::Block,
+        );
     }
 
     pub fn boulders(&self) -> impl Iterator<Item = usize> + '_ {
@@ -116,50 +96,32 @@ impl Platform {
 
     pub fn load(&self) -> usize {
         self.field
-            .iter()
-            .enumerate()
+            .iter_positions()
             .filter(|(_, tile)| matches!(tile, Tile::Boulder))
-            .map(|(idx, _)| self.height - idx / self.width)
+            .map(|(position, _)| (self.field.height() - position.y) as usize)
             .sum()
     }
 }
 
 impl std::fmt::Debug for Platform {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let border = "-".repeat(self.field.width() as usize);
+
         writeln!(f)?;
-        write!(f, "+")?;
-        for _ in 0..self.width {
-            write!(f, "-")?;
-        }
-        writeln!(f, "+")?;
-        write!(f, "|")?;
+        writeln!(f, "+{border}+")?;
 
-        for (idx, tile) in self.field.iter().enumerate() {
-            if idx > 0 && idx % self.width == 0 {
-                writeln!(f, "|")?;
-                write!(f, "|")?;
+        for row in self.field.rows() {
+            write!(f, "|")?;
+            for tile in row {
+                write!(f, "{tile:?}")?;
             }
-
-            write!(f, "{tile:?}")?;
+            writeln!(f, "|")?;
         }
 
-        writeln!(f, "|")?;
-        write!(f, "+")?;
-        for _ in 0..self.width {
-            write!(f, "-")?;
-        }
-        writeln!(f, "+")
+        writeln!(f, "+{border}+")
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum Direction {
-    North,
-    South,
-    West,
-    East,
-}
-
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Tile {
     Empty,
@@ -177,42 +139,20 @@ impl std::fmt::Debug for Tile {
     }
 }
 
-fn parse(input: &str) -> Platform {
-    let mut width = 0;
-    let mut height = 0;
-
-    let mut field = Vec::with_capacity(input.len());
-    for (y, line) in input.lines().enumerate() {
-        if y == 0 {
-            width = line.len();
-        }
-        height += 1;
-
-        for c in line.chars() {
-            let tile = match c {
-                '#' => Tile::Block,
-                'O' => Tile::Boulder,
-                _ => Tile::Empty,
-            };
-
-            field.push(tile);
-        }
-    }
-
-    assert_eq!(field.len(), width * height);
-
-    Platform {
-        width,
-        height,
-        field,
-    }
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Platform, SolveError> {
+    let field = Grid::parse(input, |c, _| {
+        Ok::<_, String>(match c {
+            '#' => Tile::Block,
+            'O' => Tile::Boulder,
+            _ => Tile::Empty,
+        })
+    })?;
+
+    Ok(Platform { field })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "O....#....
+const EXAMPLE_INPUT: &str = "O....#....
 O.OO#....#
 .....##...
 OO.#O....O
@@ -224,15 +164,19 @@ O.#..O.#.#
 #OO..#....
 ";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 136);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 64);
     }
 }
@@ -1,29 +1,61 @@
 use std::ops::IndexMut;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+use aoc_util::{
+    error::SolveError,
+    solution::{Example, Solution},
+};
+
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 15;
+    const TITLE: &'static str = "Lens Library";
+
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 1320, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 145, solve_part2)]
+    }
 }
 
-fn solve_part1(input: &str) -> usize {
-    input
-        .split([',', '\r', '\n'])
-        .filter(|s| !s.is_empty())
-        .map(hash)
-        .sum()
+fn solve_part1(input: &str) -> Result<usize, SolveError> {
+    Ok(sum_hashes(input))
+}
+
+fn solve_part2(input: &str) -> Result<usize, SolveError> {
+    let operations = parse(input)?;
+    Ok(focusing_power(operations))
+}
+
+fn steps(input: &str) -> impl Iterator<Item = &str> {
+    input.split([',', '\r', '\n']).filter(|s| !s.is_empty())
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+#[tracing::instrument(skip_all)]
+fn sum_hashes(input: &str) -> usize {
+    steps(input).map(hash).sum()
 }
 
-fn solve_part2(input: &str) -> usize {
+/// Applies the operations to the boxes and sums the focusing power of the
+/// lenses left in them.
+#[tracing::instrument(skip_all)]
+fn focusing_power(operations: Vec<Operation>) -> usize {
     let mut map = HashMap::new();
 
-    for op in input
-        .split([',', '\r', '\n'])
-        .filter(|s| !s.is_empty())
-        .map(parse)
-    {
+    for op in operations {
         match op {
             Operation::Insert(label, value) => {
                 map.insert(label, value);
@@ -47,13 +79,21 @@ fn solve_part2(input: &str) -> usize {
         .sum()
 }
 
-fn parse(input: &str) -> Operation {
-    if let Some((label, value)) = input.split_once('=') {
-        Operation::Insert(label, value.parse().unwrap())
-    } else if let Some(label) = input.strip_suffix('-') {
-        Operation::Remove(label)
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Vec<Operation<'_>>, SolveError> {
+    steps(input).map(|step| parse_step(input, step)).collect()
+}
+
+fn parse_step<'a>(input: &str, step: &'a str) -> Result<Operation<'a>, SolveError> {
+    if let Some((label, value)) = step.split_once('=') {
+        let value = value
+            .parse()
+            .map_err(|_| SolveError::at(input, value, "Expected a focal length"))?;
+        Ok(Operation::Insert(label, value))
+    } else if let Some(label) = step.strip_suffix('-') {
+        Ok(Operation::Remove(label))
     } else {
-        unreachable!("{input}")
+        Err(SolveError::at(input, step, "Expected `=` or `-`"))
     }
 }
 
@@ -100,21 +140,21 @@ impl<'a> HashMap<'a> {
     }
 }
 
+const EXAMPLE_INPUT: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
-
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 1320);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 145);
     }
 }
@@ -1,15 +1,45 @@
 use ahash::AHashSet as HashSet;
 
-use aoc_util::grid::*;
+use aoc_util::{
+    error::SolveError,
+    grid::*,
+    solution::{Example, Solution},
+};
 use rayon::prelude::*;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 16;
+    const TITLE: &'static str = "The Floor Will Be Lava";
+
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 46, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 51, solve_part2)]
+    }
 }
 
-fn solve_part1(input: &str) -> usize {
-    let contraption = parse(input);
-    count_energized_tiles_from(Position::zeros(), Direction::Right, &contraption)
+fn solve_part1(input: &str) -> Result<usize, SolveError> {
+    let contraption = parse(input)?;
+    Ok(count_energized_tiles_from(
+        Position::zeros(),
+        Direction::Right,
+        &contraption,
+    ))
 }
 
 fn count_energized_tiles_from(
@@ -56,12 +86,8 @@ fn count_energized_tiles_from(
     energy.iter().filter(|energy| **energy).count()
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> usize {
-    let contraption = parse(input);
+fn solve_part2(input: &str) -> Result<usize, SolveError> {
+    let contraption = parse(input)?;
 
     let top = (0..contraption.width()).map(|x| (Position::new(x, 0), Direction::Down));
     let bottom = (0..contraption.width())
@@ -76,7 +102,7 @@ fn solve_part2(input: &str) -> usize {
         .par_bridge()
         .map(|(position, direction)| count_energized_tiles_from(position, direction, &contraption))
         .max()
-        .unwrap()
+        .ok_or_else(|| SolveError::unsolvable("Empty contraption"))
 }
 
 #[derive(Debug)]
@@ -202,36 +228,20 @@ impl Mirror {
     }
 }
 
-fn parse(input: &str) -> Contraption {
-    let mut width = 0;
-    let mut height = 0;
-    let mut tiles = Vec::new();
-
-    for (y, line) in input.lines().enumerate() {
-        if y == 0 {
-            width = line.len();
-        }
-        height += 1;
-
-        for tile in line.chars().map(|c| match c {
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Contraption, SolveError> {
+    Contraption::parse(input, |c, _| {
+        Ok::<_, String>(match c {
             '-' => Tile::Splitter(Splitter::Horizontal),
             '|' => Tile::Splitter(Splitter::Vertical),
             '\\' => Tile::Mirror(Mirror::Down),
             '/' => Tile::Mirror(Mirror::Up),
             _ => Tile::Empty,
-        }) {
-            tiles.push(tile);
-        }
-    }
-
-    Contraption::new(width, height, tiles)
+        })
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = r".|...\....
+const EXAMPLE_INPUT: &str = r".|...\....
 |.-.\.....
 .....|-...
 ........|.
@@ -243,15 +253,19 @@ mod tests {
 ..//.|....
 ";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 46);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 51);
     }
 }
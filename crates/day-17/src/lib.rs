@@ -1,11 +1,40 @@
-use aoc_util::grid::*;
+use aoc_util::{
+    error::SolveError,
+    grid::*,
+    solution::{Example, Solution},
+};
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 17;
+    const TITLE: &'static str = "Clumsy Crucible";
+
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT1, 102, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![
+            Example::new(EXAMPLE_INPUT1, 94, solve_part2),
+            Example::new(EXAMPLE_INPUT2, 71, solve_part2),
+        ]
+    }
 }
 
-fn solve_part1(input: &str) -> u32 {
-    let map = parse(input);
+fn solve_part1(input: &str) -> Result<u32, SolveError> {
+    let map = parse(input)?;
     let goal = Position::new(map.width() - 1, map.height() - 1);
 
     let result = aoc_util::graph::search::astar(
@@ -26,19 +55,15 @@ fn solve_part1(input: &str) -> u32 {
         ],
     );
 
-    result
+    let (path, _) = result.ok_or_else(|| SolveError::unsolvable("No path to the goal"))?;
+    Ok(path
         .iter()
-        .flat_map(|(path, _)| path)
         .map(|TraversalPosition { cost, .. }| *cost as u32)
-        .sum()
-}
-
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+        .sum())
 }
 
-fn solve_part2(input: &str) -> u32 {
-    let map = parse(input);
+fn solve_part2(input: &str) -> Result<u32, SolveError> {
+    let map = parse(input)?;
     let goal = Position::new(map.width() - 1, map.height() - 1);
 
     let result = aoc_util::graph::search::astar(
@@ -59,11 +84,11 @@ fn solve_part2(input: &str) -> u32 {
         ],
     );
 
-    result
+    let (path, _) = result.ok_or_else(|| SolveError::unsolvable("No path to the goal"))?;
+    Ok(path
         .iter()
-        .flat_map(|(path, _)| path)
         .map(|TraversalPosition { cost, .. }| *cost as u32)
-        .sum()
+        .sum())
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -144,32 +169,16 @@ impl TraversalPosition {
 
 type Map = Grid<u8>;
 
-fn parse(input: &str) -> Map {
-    let mut width = 0;
-    let mut height = 0;
-    let mut blocks = Vec::new();
-    for (y, line) in input.lines().enumerate() {
-        if y == 0 {
-            width = line.len();
-        }
-
-        height += 1;
-
-        for c in line.chars() {
-            blocks.push(c.to_digit(10).unwrap() as u8);
-        }
-    }
-
-    Map::new(width, height, blocks)
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Map, SolveError> {
+    Map::parse(input, |c, _| {
+        c.to_digit(10)
+            .map(|block| block as u8)
+            .ok_or("Expected a digit")
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use rstest::rstest;
-
-    use super::*;
-
-    const TEST_INPUT1: &str = "2413432311323
+const EXAMPLE_INPUT1: &str = "2413432311323
 3215453535623
 3255245654254
 3446585845452
@@ -184,24 +193,30 @@ mod tests {
 4322674655533
 ";
 
-    const TEST_INPUT2: &str = "111111111111
+const EXAMPLE_INPUT2: &str = "111111111111
 999999999991
 999999999991
 999999999991
 999999999991
 ";
 
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT1);
+        let solution = solve_part1(EXAMPLE_INPUT1).unwrap();
         assert_eq!(solution, 102);
     }
 
     #[rstest]
-    #[case(TEST_INPUT1, 94)]
-    #[case(TEST_INPUT2, 71)]
+    #[case(EXAMPLE_INPUT1, 94)]
+    #[case(EXAMPLE_INPUT2, 71)]
     fn test_part2(#[case] input: &str, #[case] expected: u32) {
-        let solution = solve_part2(input);
+        let solution = solve_part2(input).unwrap();
         assert_eq!(solution, expected);
     }
 }
@@ -1,42 +1,60 @@
-use aoc_util::grid::{Direction, Position};
+use aoc_util::{
+    error::{Located, SolveError},
+    grid::{Direction, Position},
+    solution::{Example, Solution},
+};
 use nom::{
     bytes::complete::{tag, take},
     character::complete::{self, one_of, space1},
-    combinator::map,
+    combinator::{map, map_res},
     sequence::{delimited, pair, separated_pair},
     IResult, Parser,
 };
 use nom_supreme::final_parser::final_parser;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
-}
+pub struct Day;
 
-fn solve_part1(input: &str) -> u64 {
-    let plan = input
-        .lines()
-        .map(parse)
-        .map(Result::unwrap)
-        .map(|(incorrect, _)| incorrect);
+impl Solution for Day {
+    const ID: u32 = 18;
+    const TITLE: &'static str = "Lavaduct Lagoon";
 
-    calculate_area(plan)
-}
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 62, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 952408144115, solve_part2)]
+    }
 }
 
-fn solve_part2(input: &str) -> u64 {
-    let plan = input
-        .lines()
-        .map(parse)
-        .map(Result::unwrap)
-        .map(|(_, correct)| correct);
+fn solve_part1(input: &str) -> Result<u64, SolveError> {
+    let plan = parse(input)?;
+    Ok(calculate_area(
+        plan.into_iter().map(|(incorrect, _)| incorrect),
+    ))
+}
 
-    calculate_area(plan)
+fn solve_part2(input: &str) -> Result<u64, SolveError> {
+    let plan = parse(input)?;
+    Ok(calculate_area(plan.into_iter().map(|(_, correct)| correct)))
 }
 
-fn calculate_area(plan: impl IntoIterator<Item = (Direction, usize)>) -> u64 {
+/// Direction and distance of a single step of the dig plan.
+type Step = (Direction, usize);
+
+#[tracing::instrument(skip_all)]
+fn calculate_area(plan: impl IntoIterator<Item = Step>) -> u64 {
     let mut position = Position::zeros();
     let mut perimeter = 0;
     let mut area = 0;
@@ -55,8 +73,16 @@ fn determinant(a: Position, b: Position) -> i64 {
     a.x * b.y - b.x * a.y
 }
 
-#[allow(clippy::type_complexity)]
-fn parse(input: &str) -> Result<((Direction, usize), (Direction, usize)), nom::error::Error<&str>> {
+/// Steps of the plan, each read both as written and from its color.
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Vec<(Step, Step)>, SolveError> {
+    input
+        .lines()
+        .map(|line| parse_step(line).located(input))
+        .collect()
+}
+
+fn parse_step(input: &str) -> Result<(Step, Step), nom::error::Error<&str>> {
     fn direction_parser(input: &str) -> IResult<&str, Direction> {
         map(one_of("UDLR0123"), |c| match c {
             'U' | '3' => Direction::Up,
@@ -70,7 +96,7 @@ fn parse(input: &str) -> Result<((Direction, usize), (Direction, usize)), nom::e
     let color_parser = delimited(
         tag("(#"),
         pair(
-            take(5usize).map(|s| usize::from_str_radix(s, 16).unwrap()),
+            map_res(take(5usize), |s| usize::from_str_radix(s, 16)),
             direction_parser,
         ),
         tag(")"),
@@ -84,21 +110,12 @@ fn parse(input: &str) -> Result<((Direction, usize), (Direction, usize)), nom::e
     )
     .map(|(direction, (distance, color))| ((direction, distance), color));
 
-    final_parser::<
-        &str,
-        ((Direction, usize), (Direction, usize)),
-        nom::error::Error<&str>,
-        nom::error::Error<&str>,
-    >(parser)(input)
+    final_parser::<&str, (Step, Step), nom::error::Error<&str>, nom::error::Error<&str>>(parser)(
+        input,
+    )
 }
 
-#[cfg(test)]
-mod tests {
-    use rstest::rstest;
-
-    use super::*;
-
-    const TEST_INPUT: &str = "R 6 (#70c710)
+const EXAMPLE_INPUT: &str = "R 6 (#70c710)
 D 5 (#0dc571)
 L 2 (#5713f0)
 D 2 (#d2c081)
@@ -114,9 +131,15 @@ L 2 (#015232)
 U 2 (#7a21e3)
 ";
 
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 62);
     }
 
@@ -138,13 +161,13 @@ U 2 (#000000)
         15
     )]
     fn extras_part1(#[case] input: &str, #[case] expected: u64) {
-        let solution = solve_part1(input);
+        let solution = solve_part1(input).unwrap();
         assert_eq!(solution, expected);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 952408144115);
     }
 }
@@ -0,0 +1,109 @@
+use aoc_util::random::Rng;
+
+const CATEGORIES: [char; 4] = ['x', 'm', 'a', 's'];
+
+/// Generates a tree of `size` workflows rooted at `in`, followed by twice as
+/// many parts.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let size = size.max(1);
+
+    let mut workflows = Vec::with_capacity(size);
+    let mut pending = vec!["in".to_string()];
+    let mut created = 1;
+
+    while let Some(name) = pending.pop() {
+        let filters = rng.range(1..4);
+
+        let mut rules = Vec::new();
+        for filter in 0..=filters {
+            // Keep the tree growing until it has all its workflows
+            let grow =
+                created < size && (rng.chance(0.5) || (filter == filters && pending.is_empty()));
+            let destination = if grow {
+                let name = workflow_name(created);
+                pending.push(name.clone());
+                created += 1;
+                name
+            } else {
+                rng.choose(&["A", "R"]).to_string()
+            };
+
+            rules.push(if filter == filters {
+                destination
+            } else {
+                format!(
+                    "{}{}{}:{destination}",
+                    rng.choose(&CATEGORIES),
+                    rng.choose(&['<', '>']),
+                    rng.range(1..4001),
+                )
+            });
+        }
+
+        workflows.push(format!("{name}{{{}}}", rules.join(",")));
+    }
+
+    rng.shuffle(&mut workflows);
+
+    let mut input = workflows.join("\n");
+    input.push_str("\n\n");
+
+    for _ in 0..2 * size {
+        let ratings = CATEGORIES.map(|category| format!("{category}={}", rng.range(1..4001)));
+        input.push_str(&format!("{{{}}}\n", ratings.join(",")));
+    }
+
+    input
+}
+
+/// Three lowercase letters, so generated names never clash with `in`.
+fn workflow_name(index: usize) -> String {
+    let letters = (0..3)
+        .rev()
+        .map(|digit| (b'a' + (index / 26usize.pow(digit) % 26) as u8) as char);
+    letters.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ahash::AHashMap as HashMap;
+
+    use super::*;
+    use crate::{parser, solve_part1, solve_part2, Condition, Destination};
+
+    #[test]
+    fn test_generate() {
+        let input = generate(200, &mut Rng::new(19));
+        let (workflows, parts) = parser::parse(&input).unwrap();
+        assert_eq!(workflows.len(), 200);
+
+        let workflows = workflows
+            .iter()
+            .map(|workflow| (workflow.name, workflow))
+            .collect::<HashMap<_, _>>();
+
+        // Follows the workflows one rule at a time
+        let mut expected = 0;
+        for part in &parts {
+            let mut destination = Destination::Workflow("in");
+            while let Destination::Workflow(name) = destination {
+                let workflow = workflows[name];
+                destination = workflow
+                    .filters
+                    .iter()
+                    .find(|(filter, _)| match filter.condition {
+                        Condition::GreaterThan(target) => part.get(filter.category) > target,
+                        Condition::LessThan(target) => part.get(filter.category) < target,
+                    })
+                    .map_or(workflow.fallback, |&(_, destination)| destination);
+            }
+
+            if destination == Destination::Terminal(true) {
+                expected += part.x + part.m + part.a + part.s;
+            }
+        }
+
+        assert_eq!(solve_part1(&input).unwrap(), expected);
+        assert!(solve_part2(&input).unwrap() <= 4000u64.pow(4));
+    }
+}
@@ -1,35 +1,86 @@
 use ahash::AHashMap as HashMap;
 use aoc_util::{
-    range::MultiRange,
+    error::{Located, SolveError},
+    random::Rng,
+    solution::{Example, Phases, Solution},
     tree::kdtree::{DimensionCollection, KdTree, KdTreeBuilderNode},
 };
 use rayon::prelude::*;
 
+mod generate;
 mod parser;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 19;
+    const TITLE: &'static str = "Aplenty";
+
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 19114, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 167409079868000, solve_part2)]
+    }
+
+    fn part1_phases() -> Option<Phases<Self::Answer1>> {
+        Some(Phases::new(parse, accepted_ratings))
+    }
+
+    fn part2_phases() -> Option<Phases<Self::Answer2>> {
+        Some(Phases::new(parse_workflows, accepted_combinations))
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Option<String> {
+        Some(generate::generate(size, rng))
+    }
 }
 
-fn solve_part1(input: &str) -> u64 {
-    let (workflows, parts) = parser::parse(input).unwrap();
-    let workflows = Workflows::from_iter(workflows);
+fn solve_part1(input: &str) -> Result<u64, SolveError> {
+    accepted_ratings(&parse(input)?)
+}
 
-    parts
-        .into_par_iter()
-        .filter(|part| workflows.accepts(part))
-        .map(|part| part.x + part.m + part.a + part.s)
-        .sum()
+fn solve_part2(input: &str) -> Result<u64, SolveError> {
+    accepted_combinations(&parse_workflows(input)?)
+}
+
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<(Workflows, Vec<Part>), SolveError> {
+    let (workflows, parts) = parser::parse(input).located(input)?;
+    Ok((Workflows::from_iter(workflows), parts))
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+#[tracing::instrument(skip_all)]
+fn parse_workflows(input: &str) -> Result<Workflows, SolveError> {
+    let workflows = parser::parse_workflows(input).located(input)?;
+    Ok(Workflows::from_iter(workflows))
 }
 
-fn solve_part2(input: &str) -> u64 {
-    let workflows = parser::parse_workflows(input).unwrap();
-    let workflows = Workflows::from_iter(workflows);
+#[tracing::instrument(skip_all)]
+fn accepted_ratings((workflows, parts): &(Workflows, Vec<Part>)) -> Result<u64, SolveError> {
+    let sum = parts
+        .par_iter()
+        .filter(|part| workflows.accepts(part))
+        .map(|part| part.x + part.m + part.a + part.s)
+        .sum();
 
+    Ok(sum)
+}
+
+#[tracing::instrument(skip_all)]
+fn accepted_combinations(workflows: &Workflows) -> Result<u64, SolveError> {
     let mut count = 0;
     let _: Option<()> = workflows.tree.traverse(|&accepted, ranges| {
         if !accepted {
@@ -42,7 +93,13 @@ fn solve_part2(input: &str) -> u64 {
                 ranges
                     .iter()
                     .find(|(c, _)| *c == category)
-                    .map(|(_, range)| range.end.unwrap_or(4001) - range.start.unwrap_or(1))
+                    // Contradicting rules leave an empty range
+                    .map(|(_, range)| {
+                        range
+                            .end
+                            .unwrap_or(4001)
+                            .saturating_sub(range.start.unwrap_or(1))
+                    })
                     .unwrap_or(4000)
             })
             .product::<u64>();
@@ -50,7 +107,7 @@ fn solve_part2(input: &str) -> u64 {
         None
     });
 
-    count
+    Ok(count)
 }
 
 struct Workflows {
@@ -174,19 +231,7 @@ enum Destination<'a> {
     Workflow(&'a str),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Ranges {
-    x: MultiRange<u64>,
-    m: MultiRange<u64>,
-    a: MultiRange<u64>,
-    s: MultiRange<u64>,
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "px{a<2006:qkq,m>2090:A,rfg}
+const EXAMPLE_INPUT: &str = "px{a<2006:qkq,m>2090:A,rfg}
 pv{a>1716:R,A}
 lnx{m>1548:A,A}
 rfg{s<537:gd,x>2440:R,A}
@@ -205,15 +250,19 @@ hdj{m>838:A,pv}
 {x=2127,m=1623,a=2188,s=1013}
 ";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 19114);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 167409079868000);
     }
 }
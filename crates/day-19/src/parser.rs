@@ -12,23 +12,23 @@ use nom_supreme::final_parser::final_parser;
 
 use crate::{Category, Condition, Destination, Filter, Part, Workflow};
 
-pub fn parse(input: &str) -> Result<(Vec<Workflow>, Vec<Part>), nom::error::Error<&str>> {
+pub fn parse(input: &str) -> Result<(Vec<Workflow<'_>>, Vec<Part>), nom::error::Error<&str>> {
     final_parser(parser)(input)
 }
 
-pub fn parse_workflows(input: &str) -> Result<Vec<Workflow>, nom::error::Error<&str>> {
+pub fn parse_workflows(input: &str) -> Result<Vec<Workflow<'_>>, nom::error::Error<&str>> {
     final_parser(terminated(workflows_parser, rest))(input)
 }
 
-fn parser(input: &str) -> IResult<&str, (Vec<Workflow>, Vec<Part>)> {
+fn parser(input: &str) -> IResult<&str, (Vec<Workflow<'_>>, Vec<Part>)> {
     separated_pair(workflows_parser, line_ending, parts_parser)(input)
 }
 
-fn workflows_parser(input: &str) -> IResult<&str, Vec<Workflow>> {
+fn workflows_parser(input: &str) -> IResult<&str, Vec<Workflow<'_>>> {
     many1(terminated(workflow_parser, line_ending))(input)
 }
 
-fn workflow_parser(input: &str) -> IResult<&str, Workflow> {
+fn workflow_parser(input: &str) -> IResult<&str, Workflow<'_>> {
     map(
         pair(
             alpha1,
@@ -44,15 +44,15 @@ fn workflow_parser(input: &str) -> IResult<&str, Workflow> {
 
 fn workflow_definition_parser(
     input: &str,
-) -> IResult<&str, (Vec<(Filter, Destination)>, Destination)> {
+) -> IResult<&str, (Vec<(Filter, Destination<'_>)>, Destination<'_>)> {
     separated_pair(workflow_filters_parser, tag(","), destination_parser)(input)
 }
 
-fn workflow_filters_parser(input: &str) -> IResult<&str, Vec<(Filter, Destination)>> {
+fn workflow_filters_parser(input: &str) -> IResult<&str, Vec<(Filter, Destination<'_>)>> {
     separated_list1(tag(","), workflow_filter_parser)(input)
 }
 
-fn workflow_filter_parser(input: &str) -> IResult<&str, (Filter, Destination)> {
+fn workflow_filter_parser(input: &str) -> IResult<&str, (Filter, Destination<'_>)> {
     separated_pair(filter_parser, tag(":"), destination_parser)(input)
 }
 
@@ -84,7 +84,7 @@ fn condition_parser(input: &str) -> IResult<&str, Condition> {
     })(input)
 }
 
-fn destination_parser(input: &str) -> IResult<&str, Destination> {
+fn destination_parser(input: &str) -> IResult<&str, Destination<'_>> {
     map(alpha1, |s| match s {
         "A" => Destination::Terminal(true),
         "R" => Destination::Terminal(false),
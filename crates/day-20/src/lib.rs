@@ -1,16 +1,83 @@
 use std::collections::VecDeque;
 
 use ahash::AHashMap as HashMap;
+use aoc_util::{
+    error::{Located, SolveError},
+    solution::{Example, Phases, Solution},
+    validation::Check,
+};
 
 mod parser;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 20;
+    const TITLE: &'static str = "Pulse Propagation";
+
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![
+            Example::new(EXAMPLE_INPUT1, 32000000, solve_part1),
+            Example::new(EXAMPLE_INPUT2, 11687500, solve_part1),
+        ]
+    }
+
+    fn part1_phases() -> Option<Phases<Self::Answer1>> {
+        Some(Phases::new(parse, pulse_product))
+    }
+
+    fn part2_phases() -> Option<Phases<Self::Answer2>> {
+        Some(Phases::new(parse, presses_until_rx))
+    }
+
+    fn validate(input: &str) -> Result<Vec<Check>, SolveError> {
+        let network = parse(input)?;
+        let conjunction = rx_conjunction(&network);
+
+        let mut checks = vec![Check::new(
+            2,
+            "A single conjunction feeds rx",
+            conjunction.clone().map(|_| ()),
+        )];
+
+        if let Ok(conjunction) = conjunction {
+            checks.push(Check::new(
+                2,
+                "Every input of that conjunction sends it a high pulse on a cycle starting at the first press",
+                check_clean_cycles(&network, conjunction),
+            ));
+        }
+
+        Ok(checks)
+    }
+}
+
+fn solve_part1(input: &str) -> Result<u64, SolveError> {
+    pulse_product(&parse(input)?)
+}
+
+fn solve_part2(input: &str) -> Result<u64, SolveError> {
+    presses_until_rx(&parse(input)?)
+}
+
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Network, SolveError> {
+    Network::new(parser::parse(input).located(input)?)
 }
 
-fn solve_part1(input: &str) -> u64 {
-    let modules = parser::parse(input).unwrap();
-    let network = Network::from_iter(modules);
+#[tracing::instrument(skip_all)]
+fn pulse_product(network: &Network) -> Result<u64, SolveError> {
     let mut state = network.new_state();
 
     let mut low_pulses = 0;
@@ -28,80 +95,132 @@ fn solve_part1(input: &str) -> u64 {
         });
     }
 
-    low_pulses * high_pulses
+    Ok(low_pulses * high_pulses)
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
-}
+/// rx receives a low pulse once all inputs of the conjunction feeding it send
+/// a high pulse in the same press. Every input does so on a cycle of its own
+/// that starts at the first press, so this happens after the least common
+/// multiple of the cycle lengths.
+#[tracing::instrument(skip_all)]
+fn presses_until_rx(network: &Network) -> Result<u64, SolveError> {
+    let conjunction = rx_conjunction(network).map_err(SolveError::unsolvable)?;
 
-fn solve_part2(input: &str) -> u64 {
-    let modules = parser::parse(input).unwrap();
-    let network = Network::from_iter(modules);
+    high_pulse_presses(network, conjunction, 1)
+        .iter()
+        .map(|presses| {
+            presses
+                .first()
+                .copied()
+                .ok_or_else(|| SolveError::unsolvable("No cycle found"))
+        })
+        .try_fold(1, |acc, length| {
+            Ok(aoc_util::numerics::least_common_multiple(acc, length?))
+        })
+}
 
-    let mut rx_index = network
+/// Follows rx back through modules with a single input to the conjunction
+/// with several inputs that decides when rx receives a low pulse.
+fn rx_conjunction(network: &Network) -> Result<usize, String> {
+    let mut index = network
         .modules
         .iter()
         .position(|module| module.name == "rx")
-        .expect("No rx module");
-
-    while network.modules[rx_index].inputs.len() == 1 {
-        assert!(
-            matches!(
-                network.modules[rx_index].module_type,
-                ModuleType::None | ModuleType::Conjunction(..)
-            ),
-            "Path between rx and its first conjunction input must be unique and uninterrupted"
-        );
+        .ok_or("there is no rx module")?;
+
+    while network.modules[index].inputs.len() == 1 {
+        let module = &network.modules[index];
+        if !matches!(
+            module.module_type,
+            ModuleType::None | ModuleType::Conjunction(..)
+        ) {
+            return Err(format!(
+                "{} between rx and its conjunction is not a conjunction",
+                module.name
+            ));
+        }
 
-        rx_index = network.modules[rx_index].inputs[0]
+        index = module.inputs[0];
     }
 
-    assert!(
-        matches!(
-            network.modules[rx_index].module_type,
-            ModuleType::Conjunction(..)
-        ),
-        "Observed module must be a conjunction"
-    );
+    let module = &network.modules[index];
+    match module.module_type {
+        ModuleType::Conjunction(..) => Ok(index),
+        _ if module.inputs.is_empty() => Err(format!("{} has no inputs", module.name)),
+        _ => Err(format!("{} feeding rx is not a conjunction", module.name)),
+    }
+}
 
-    let observed_indices = network.modules[rx_index].inputs.clone();
-    let mut cycle_lengths = vec![None; observed_indices.len()];
+/// Longest cycle of an input of the conjunction feeding rx that is searched for.
+const MAX_CYCLE: u64 = 5000;
 
-    let mut presses = 0;
+/// Presses in which every input of `conjunction` sends it a high pulse, up to
+/// `count` presses per input.
+fn high_pulse_presses(network: &Network, conjunction: usize, count: usize) -> Vec<Vec<u64>> {
+    let inputs = &network.modules[conjunction].inputs;
+    let mut presses = vec![Vec::with_capacity(count); inputs.len()];
     let mut state = network.new_state();
 
-    loop {
-        presses += 1;
+    for press in 1..=MAX_CYCLE * count as u64 {
         network.broadcast(Pulse::Low, &mut state, |source, destination, pulse| {
-            if destination != rx_index || pulse != Pulse::High {
+            if destination != conjunction || pulse != Pulse::High {
                 return;
             }
 
-            if let Some(idx) = observed_indices.iter().position(|idx| *idx == source) {
-                cycle_lengths[idx] = Some(presses);
+            if let Some(idx) = inputs.iter().position(|input| *input == source) {
+                let input_presses = &mut presses[idx];
+                if input_presses.len() < count && input_presses.last() != Some(&press) {
+                    input_presses.push(press);
+                }
             }
         });
 
-        if cycle_lengths.iter().all(|it| it.is_some()) || presses > 5000 {
+        if presses
+            .iter()
+            .all(|input_presses| input_presses.len() == count)
+        {
             break;
         }
     }
 
-    cycle_lengths
+    presses
+}
+
+fn check_clean_cycles(network: &Network, conjunction: usize) -> Result<(), String> {
+    let inputs = &network.modules[conjunction].inputs;
+
+    for (input, presses) in inputs
         .iter()
-        .map(|it| it.expect("No cycle found"))
-        .reduce(aoc_util::numerics::least_common_multiple)
-        .unwrap()
+        .zip(high_pulse_presses(network, conjunction, 2))
+    {
+        let name = &network.modules[*input].name;
+
+        match presses[..] {
+            [first, second] if second == 2 * first => {}
+            [first, second] => {
+                return Err(format!(
+                    "{name} sends high pulses in presses {first} and {second}"
+                ))
+            }
+            _ => {
+                return Err(format!(
+                    "{name} sends fewer than two high pulses in {} presses",
+                    2 * MAX_CYCLE
+                ))
+            }
+        }
+    }
+
+    Ok(())
 }
 
 #[derive(Debug, Clone)]
-struct Network<'a> {
-    modules: Vec<NetworkModule<'a>>,
+struct Network {
+    modules: Vec<NetworkModule>,
     broadcaster: usize,
 }
 
-impl<'a> Network<'a> {
+impl Network {
     pub fn new_state(&self) -> Vec<ModuleState> {
         self.modules
             .iter()
@@ -136,11 +255,11 @@ impl<'a> Network<'a> {
     }
 }
 
-impl<'a> FromIterator<Module<'a>> for Network<'a> {
-    fn from_iter<T: IntoIterator<Item = Module<'a>>>(iter: T) -> Self {
+impl Network {
+    pub fn new<'a>(modules: impl IntoIterator<Item = Module<'a>>) -> Result<Self, SolveError> {
         let mut builder = NetworkBuilder::default();
 
-        for module in iter.into_iter() {
+        for module in modules.into_iter() {
             builder.add_module(&module);
         }
 
@@ -151,7 +270,7 @@ impl<'a> FromIterator<Module<'a>> for Network<'a> {
 #[derive(Default)]
 struct NetworkBuilder<'a> {
     name_mapping: HashMap<&'a str, usize>,
-    modules: Vec<NetworkModule<'a>>,
+    modules: Vec<NetworkModule>,
     module_inputs: Vec<Vec<usize>>,
     broadcaster: Option<usize>,
 }
@@ -166,7 +285,7 @@ impl<'a> NetworkBuilder<'a> {
         network_module.destinations = destinations;
     }
 
-    pub fn build(mut self) -> Network<'a> {
+    pub fn build(mut self) -> Result<Network, SolveError> {
         for (idx, inputs) in self.module_inputs.into_iter().enumerate() {
             if let ModuleType::Conjunction(input_count) = &mut self.modules[idx].module_type {
                 *input_count = inputs.len();
@@ -175,10 +294,14 @@ impl<'a> NetworkBuilder<'a> {
             self.modules[idx].inputs = inputs;
         }
 
-        Network {
+        let broadcaster = self
+            .broadcaster
+            .ok_or_else(|| SolveError::unsolvable("No broadcaster module"))?;
+
+        Ok(Network {
             modules: self.modules,
-            broadcaster: self.broadcaster.expect("No broadcaster module"),
-        }
+            broadcaster,
+        })
     }
 
     fn register_name(&mut self, name: &'a str) -> usize {
@@ -186,7 +309,7 @@ impl<'a> NetworkBuilder<'a> {
             *idx
         } else {
             let idx = self.modules.len();
-            self.modules.push(NetworkModule::empty(name));
+            self.modules.push(NetworkModule::empty(name.to_string()));
             self.module_inputs.push(Vec::new());
             self.name_mapping.insert(name, idx);
             idx
@@ -220,16 +343,16 @@ impl<'a> NetworkBuilder<'a> {
 }
 
 #[derive(Debug, Clone)]
-struct NetworkModule<'a> {
-    name: &'a str,
+struct NetworkModule {
+    name: String,
     module_type: ModuleType,
     inputs: Vec<usize>,
     destinations: Vec<usize>,
 }
 
-impl<'a> NetworkModule<'a> {
+impl NetworkModule {
     pub fn new(
-        name: &'a str,
+        name: String,
         module_type: ModuleType,
         inputs: Vec<usize>,
         destinations: Vec<usize>,
@@ -242,7 +365,7 @@ impl<'a> NetworkModule<'a> {
         }
     }
 
-    pub fn empty(name: &'a str) -> Self {
+    pub fn empty(name: String) -> Self {
         Self::new(name, ModuleType::None, Vec::new(), Vec::new())
     }
 }
@@ -339,31 +462,52 @@ enum Pulse {
     High,
 }
 
-#[cfg(test)]
-mod tests {
-    use rstest::rstest;
-
-    use super::*;
-
-    const TEST_INPUT1: &str = "broadcaster -> a, b, c
+const EXAMPLE_INPUT1: &str = "broadcaster -> a, b, c
 %a -> b
 %b -> c
 %c -> inv
 &inv -> a
 ";
 
-    const TEST_INPUT2: &str = "broadcaster -> a
+const EXAMPLE_INPUT2: &str = "broadcaster -> a
 %a -> inv, con
 &inv -> b
 %b -> con
 &con -> output
 ";
 
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
     #[rstest]
-    #[case(TEST_INPUT1, 32000000)]
-    #[case(TEST_INPUT2, 11687500)]
+    #[case(EXAMPLE_INPUT1, 32000000)]
+    #[case(EXAMPLE_INPUT2, 11687500)]
     fn test_part1(#[case] input: &str, #[case] expected: u64) {
-        let solution = solve_part1(input);
+        let solution = solve_part1(input).unwrap();
         assert_eq!(solution, expected);
     }
+
+    #[test]
+    fn test_validate() {
+        let checks = Day::validate(EXAMPLE_INPUT2).unwrap();
+        assert_eq!(checks.len(), 1);
+        assert_eq!(
+            checks[0].violation.as_deref(),
+            Some("there is no rx module")
+        );
+
+        let input = "broadcaster -> a, b
+%a -> ca
+%b -> cb
+&ca -> con
+&cb -> con
+&con -> rx
+";
+        let checks = Day::validate(input).unwrap();
+        assert!(checks.iter().all(Check::holds), "{checks:?}");
+        assert_eq!(solve_part2(input).unwrap(), 2);
+    }
 }
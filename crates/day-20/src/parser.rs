@@ -10,15 +10,15 @@ use nom::{
 };
 use nom_supreme::final_parser::final_parser;
 
-pub fn parse(input: &str) -> Result<Vec<Module>, nom::error::Error<&str>> {
+pub fn parse(input: &str) -> Result<Vec<Module<'_>>, nom::error::Error<&str>> {
     final_parser(parser)(input)
 }
 
-fn parser(input: &str) -> IResult<&str, Vec<Module>> {
+fn parser(input: &str) -> IResult<&str, Vec<Module<'_>>> {
     many1(terminated(node_parser, line_ending))(input)
 }
 
-fn node_parser(input: &str) -> IResult<&str, Module> {
+fn node_parser(input: &str) -> IResult<&str, Module<'_>> {
     map(
         separated_pair(module_parser, tag(" -> "), destinations_parser),
         |((module_type, name), destinations)| Module {
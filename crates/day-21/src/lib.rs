@@ -1,21 +1,68 @@
-use aoc_util::grid::{Direction, Grid, Position, TileChar};
+use aoc_util::{
+    error::SolveError,
+    grid::{Grid, Neighborhood, Position, ReadGrid, TileChar, TiledGrid},
+    solution::{Example, Solution},
+    validation::{self, Check},
+};
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input, 64)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 21;
+    const TITLE: &'static str = "Step Counter";
+
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input, 64)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input, 26501365)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![
+            Example::new(EXAMPLE_INPUT1, 16, |input| solve_part1(input, 6))
+                .with_parameters(&[("steps", 6)]),
+        ]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![
+            Example::new(EXAMPLE_INPUT2, 25, |input| solve_part2(input, 4))
+                .with_parameters(&[("steps", 4)]),
+            Example::new(EXAMPLE_INPUT2, 64, |input| solve_part2(input, 7))
+                .with_parameters(&[("steps", 7)]),
+            Example::new(EXAMPLE_INPUT2, 121, |input| solve_part2(input, 10))
+                .with_parameters(&[("steps", 10)]),
+            Example::new(EXAMPLE_INPUT2, 196, |input| solve_part2(input, 13))
+                .with_parameters(&[("steps", 13)]),
+        ]
+    }
+
+    fn validate(input: &str) -> Result<Vec<Check>, SolveError> {
+        let (map, start) = parse(input)?;
+        Ok(check_repeating_map(&map, start))
+    }
 }
 
-fn solve_part1(input: &str, steps: usize) -> u64 {
-    let (map, start) = parse(input);
+fn solve_part1(input: &str, steps: usize) -> Result<u64, SolveError> {
+    let (map, start) = parse(input)?;
+    Ok(reachable(&map, start, steps))
+}
 
+/// Counts the gardens that can be the end of a walk of exactly `steps` steps.
+#[tracing::instrument(skip_all)]
+fn reachable(map: &impl ReadGrid<Tile = Tile>, start: Position, steps: usize) -> u64 {
     let mut even_counter = 0;
     let mut odd_counter = 0;
     aoc_util::graph::search::breadth_first_search(
         |pos, _| {
-            let pos = *pos;
-            Direction::ALL
-                .into_iter()
-                .map(move |direction| pos + direction)
-                .filter(|neighbor| matches!(map.get(neighbor), Some(Tile::Garden)))
+            map.neighbors(pos, Neighborhood::Orthogonal)
+                .filter(|(_, tile)| **tile == Tile::Garden)
+                .map(|(neighbor, _)| neighbor)
         },
         |_, depth| {
             if depth > steps {
@@ -33,159 +80,96 @@ fn solve_part1(input: &str, steps: usize) -> u64 {
         [start],
     );
 
-    if steps % 2 == 0 {
+    if steps.is_multiple_of(2) {
         even_counter
     } else {
         odd_counter
     }
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input, 26501365)
-}
-
-fn solve_part2(input: &str, steps: usize) -> u64 {
-    let (map, start) = parse(input);
-
-    assert_eq!(map.width(), map.height(), "Map must be squared");
-    let size = map.width();
-
-    assert!(size % 2 == 1, "Map must have an odd size");
-
-    assert_eq!(start.x, size / 2, "Start must be centered in the map");
-    assert_eq!(start.y, size / 2, "Start must be centered in the map");
-    assert_eq!(steps as i64 % size, size / 2);
-
-    for i in 0..size {
-        assert_eq!(
-            map[(start.y * size + i) as usize],
-            Tile::Garden,
-            "Map must be clear along the start row"
-        );
+/// Counts the gardens at the end of walks over the infinitely repeated map.
+/// The count for `n` more copies of the map grows quadratically with `n` on
+/// maps that pass [`check_repeating_map`], so it is extrapolated from the
+/// walks over 0, 1 and 2 more copies.
+fn solve_part2(input: &str, steps: usize) -> Result<u64, SolveError> {
+    let (map, start) = parse(input)?;
+    let size = map.width() as usize;
+
+    validation::first_violation(&check_repeating_map(&map, start))?;
+    if steps % size != size / 2 {
+        return Err(SolveError::unsolvable(
+            "Steps must end on the edge of a repeated map",
+        ));
+    }
 
-        assert_eq!(
-            map[(start.x + i * size) as usize],
-            Tile::Garden,
-            "Map must be clear along the start column"
-        );
+    let map = TiledGrid::new(map);
+    let (copies, rest) = (steps / size, steps % size);
+    if copies <= 2 {
+        return Ok(reachable(&map, start, steps));
     }
 
-    let grid_size = steps as i64 / size - 1;
+    let [first, second, third] =
+        [0, 1, 2].map(|copy| reachable(&map, start, rest + copy * size) as i64);
+    let copies = copies as i64;
+    let count = first
+        + copies * (second - first)
+        + copies * (copies - 1) / 2 * (third - 2 * second + first);
 
-    let mut even_distance_positions = Vec::with_capacity(map.len() / 2 + 1);
-    let mut odd_distance_positions = Vec::with_capacity(map.len() / 2 + 1);
-    aoc_util::graph::search::breadth_first_search(
-        |pos, _| {
-            let pos = *pos;
-            Direction::ALL
-                .into_iter()
-                .map(move |direction| pos + direction)
-                .filter(|neighbor| matches!(map.get(neighbor), Some(Tile::Garden)))
-        },
-        |pos, depth| {
-            if depth % 2 == 0 {
-                even_distance_positions.push(*pos);
-            } else {
-                odd_distance_positions.push(*pos);
-            }
+    Ok(count as u64)
+}
 
-            None as Option<()>
-        },
-        [start],
-    );
+/// Part 2 extrapolates the count over whole copies of the map, which only
+/// grows quadratically if the walk reaches the centre of every edge of the
+/// start copy in a straight line.
+fn check_repeating_map(map: &Map, start: Position) -> Vec<Check> {
+    let (width, height) = (map.width(), map.height());
+    let center = Position::new(width / 2, height / 2);
 
-    let (even_grid_positions, odd_grid_positions) = if steps % 2 == 0 {
-        (even_distance_positions, odd_distance_positions)
+    let square = if width == height {
+        Ok(())
     } else {
-        (odd_distance_positions, even_distance_positions)
+        Err(format!("the map is {width}x{height}"))
     };
 
-    let (even_grid, odd_grid) = (
-        even_grid_positions.len() as u64,
-        odd_grid_positions.len() as u64,
-    );
-    let (corners, small_edges, large_edges) = if grid_size % 2 == 0 {
-        (
-            count_corners(start, size, &odd_grid_positions),
-            count_small_edges(start, size, &even_grid_positions),
-            count_large_edges(start, size, &odd_grid_positions),
-        )
+    let odd = if width % 2 == 1 {
+        Ok(())
     } else {
-        (
-            count_corners(start, size, &even_grid_positions),
-            count_small_edges(start, size, &odd_grid_positions),
-            count_large_edges(start, size, &even_grid_positions),
-        )
+        Err(format!("the map is {width} tiles wide"))
     };
 
-    let even_grid_count = {
-        let tmp = (grid_size as u64 / 2) * 2 + 1;
-        tmp * tmp
-    };
-
-    let odd_grid_count = {
-        let tmp = ((grid_size as u64 + 1) / 2) * 2;
-        tmp * tmp
+    let centered = if start == center {
+        Ok(())
+    } else {
+        Err(format!(
+            "the start is at ({}, {}) instead of ({}, {})",
+            start.x, start.y, center.x, center.y
+        ))
     };
 
-    let small_edge_count = grid_size as u64 + 1;
-    let large_edge_count = grid_size as u64;
-
-    (even_grid_count * even_grid)
-        + (odd_grid_count * odd_grid)
-        + corners
-        + (small_edge_count * small_edges)
-        + (large_edge_count * large_edges)
-}
-
-fn count_from(start: Position, steps: i64, candidates: &[Position]) -> u64 {
-    candidates
-        .iter()
-        .map(|&p| aoc_util::geometry::manhattan_distance(p, start))
-        .filter(|&d| d <= steps)
-        .count() as u64
-}
-
-fn count_corners(start: Position, size: i64, candidates: &[Position]) -> u64 {
-    let starts = [
-        Position::new(start.x, size - 1),
-        Position::new(start.x, 0),
-        Position::new(size - 1, start.y),
-        Position::new(0, start.y),
-    ];
-
-    starts
-        .into_iter()
-        .map(|pos| count_from(pos, size - 1, candidates))
-        .sum()
-}
-
-fn count_small_edges(_start: Position, size: i64, candidates: &[Position]) -> u64 {
-    let starts = [
-        Position::new(0, 0),
-        Position::new(0, size - 1),
-        Position::new(size - 1, 0),
-        Position::new(size - 1, size - 1),
-    ];
-
-    starts
-        .into_iter()
-        .map(|pos| count_from(pos, size / 2 - 1, candidates))
-        .sum()
+    let row = first_rock(map.row(start.y as usize).iter(), |x| {
+        Position::new(x as i64, start.y)
+    });
+    let column = first_rock(map.column(start.x as usize), |y| {
+        Position::new(start.x, y as i64)
+    });
+
+    vec![
+        Check::new(2, "The map is square", square),
+        Check::new(2, "The map has an odd size", odd),
+        Check::new(2, "The start is in the centre of the map", centered),
+        Check::new(2, "The row of the start is clear", row),
+        Check::new(2, "The column of the start is clear", column),
+    ]
 }
 
-fn count_large_edges(_start: Position, size: i64, candidates: &[Position]) -> u64 {
-    let starts = [
-        Position::new(0, 0),
-        Position::new(0, size - 1),
-        Position::new(size - 1, 0),
-        Position::new(size - 1, size - 1),
-    ];
-
-    starts
-        .into_iter()
-        .map(|pos| count_from(pos, 3 * size / 2 - 1, candidates))
-        .sum()
+fn first_rock<'a>(
+    mut tiles: impl Iterator<Item = &'a Tile>,
+    position: impl Fn(usize) -> Position,
+) -> Result<(), String> {
+    match tiles.position(|tile| *tile != Tile::Garden).map(position) {
+        Some(rock) => Err(format!("rock at ({}, {})", rock.x, rock.y)),
+        None => Ok(()),
+    }
 }
 
 type Map = Grid<Tile>;
@@ -205,48 +189,21 @@ impl TileChar for Tile {
     }
 }
 
-fn parse(input: &str) -> (Map, Position) {
-    let mut width = 0;
-    let mut height = 0;
-    let mut tiles = Vec::new();
-    let mut start = None;
-
-    for (y, line) in input.lines().enumerate() {
-        if y == 0 {
-            width = line.len();
-        }
-        height += 1;
-
-        for (x, c) in line.chars().enumerate() {
-            match c {
-                '.' => {
-                    tiles.push(Tile::Garden);
-                }
-                '#' => {
-                    tiles.push(Tile::Rock);
-                }
-                'S' => {
-                    tiles.push(Tile::Garden);
-                    start = Some(Position::new(x as i64, y as i64));
-                }
-                _ => unreachable!(),
-            }
-        }
-    }
-
-    (
-        Map::new(width, height, tiles),
-        start.expect("No start position"),
-    )
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<(Map, Position), SolveError> {
+    let (map, starts) = Map::parse_marked(input, 'S', |c, _| match c {
+        '.' | 'S' => Ok(Tile::Garden),
+        '#' => Ok(Tile::Rock),
+        _ => Err("Expected `.`, `#` or `S`"),
+    })?;
+
+    let start = starts
+        .first()
+        .ok_or_else(|| SolveError::unsolvable("No start position"))?;
+    Ok((map, *start))
 }
 
-#[cfg(test)]
-mod tests {
-    use rstest::rstest;
-
-    use super::*;
-
-    const TEST_INPUT1: &str = "...........
+const EXAMPLE_INPUT1: &str = "...........
 .....###.#.
 .###.##..#.
 ..#.#...#..
@@ -259,24 +216,69 @@ mod tests {
 ...........
 ";
 
+const EXAMPLE_INPUT2: &str = "...
+.S.
+...";
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    /// Counts the gardens at the end of walks over the infinitely repeated map
+    /// by walking them, for the examples that [`solve_part2`] cannot
+    /// extrapolate.
+    fn walk_tiled(input: &str, steps: usize) -> Result<u64, SolveError> {
+        let (map, start) = parse(input)?;
+        Ok(reachable(&TiledGrid::new(map), start, steps))
+    }
+
     #[rstest]
-    #[case(TEST_INPUT1, 6, 16)]
+    #[case(EXAMPLE_INPUT1, 6, 16)]
     fn test_part1(#[case] input: &str, #[case] steps: usize, #[case] expected: u64) {
-        let solution = solve_part1(input, steps);
+        let solution = solve_part1(input, steps).unwrap();
         assert_eq!(solution, expected);
     }
 
-    const TEST_INPUT2: &str = "...
-.S.
-...";
-
     #[rstest]
-    #[case(TEST_INPUT2, 4, 25)]
-    #[case(TEST_INPUT2, 7, 64)]
-    #[case(TEST_INPUT2, 10, 121)]
-    #[case(TEST_INPUT2, 13, 196)]
+    #[case(EXAMPLE_INPUT2, 4, 25)]
+    #[case(EXAMPLE_INPUT2, 7, 64)]
+    #[case(EXAMPLE_INPUT2, 10, 121)]
+    #[case(EXAMPLE_INPUT2, 13, 196)]
     fn test_part2(#[case] input: &str, #[case] steps: usize, #[case] expected: u64) {
-        let solution = solve_part2(input, steps);
+        let solution = solve_part2(input, steps).unwrap();
+        assert_eq!(solution, expected);
+    }
+
+    #[rstest]
+    #[case(EXAMPLE_INPUT2, 13, 196)]
+    #[case(EXAMPLE_INPUT1, 6, 16)]
+    #[case(EXAMPLE_INPUT1, 10, 50)]
+    #[case(EXAMPLE_INPUT1, 50, 1594)]
+    #[case(EXAMPLE_INPUT1, 100, 6536)]
+    #[case(EXAMPLE_INPUT1, 500, 167004)]
+    fn test_walk_tiled(#[case] input: &str, #[case] steps: usize, #[case] expected: u64) {
+        let solution = walk_tiled(input, steps).unwrap();
         assert_eq!(solution, expected);
     }
+
+    #[test]
+    fn test_part2_unrepeating() {
+        assert!(solve_part2(EXAMPLE_INPUT1, 5).is_err());
+        assert!(solve_part2(EXAMPLE_INPUT2, 12).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let checks = Day::validate(EXAMPLE_INPUT2).unwrap();
+        assert!(checks.iter().all(Check::holds), "{checks:?}");
+
+        let violations = Day::validate(EXAMPLE_INPUT1)
+            .unwrap()
+            .into_iter()
+            .filter_map(|check| check.violation)
+            .collect::<Vec<_>>();
+        assert_eq!(violations, ["rock at (1, 5)", "rock at (5, 1)"]);
+    }
 }
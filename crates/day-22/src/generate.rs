@@ -0,0 +1,94 @@
+use aoc_util::random::Rng;
+
+/// Side of the square the bricks are dropped onto, as in the real inputs
+const FLOOR: usize = 10;
+
+/// Generates a snapshot of `size` non-overlapping bricks hanging over a ten
+/// by ten floor, listed in random order.
+pub fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut heights = [[0; FLOOR]; FLOOR];
+    let mut bricks = Vec::with_capacity(size);
+
+    for _ in 0..size.max(1) {
+        let length = rng.index(4);
+        let (x, y) = (rng.index(FLOOR), rng.index(FLOOR));
+        let (dx, dy, dz) = match rng.index(3) {
+            0 => (length.min(FLOOR - 1 - x), 0, 0),
+            1 => (0, length.min(FLOOR - 1 - y), 0),
+            _ => (0, 0, length),
+        };
+
+        let footprint = || (x..=x + dx).flat_map(|x| (y..=y + dy).map(move |y| (x, y)));
+        let z = footprint().map(|(x, y)| heights[x][y]).max().unwrap() + 1 + rng.index(3);
+        for (x, y) in footprint() {
+            heights[x][y] = z + dz;
+        }
+
+        bricks.push(format!("{x},{y},{z}~{},{},{}\n", x + dx, y + dy, z + dz));
+    }
+
+    rng.shuffle(&mut bricks);
+    bricks.concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use ahash::AHashSet as HashSet;
+    use aoc_util::grid3::{Axis, Direction3};
+
+    use super::*;
+    use crate::{parser, solve_part1, solve_part2, Brick};
+
+    /// Lets the bricks fall one voxel at a time, returning how many moved.
+    fn settle(bricks: &mut [Brick]) -> u64 {
+        let mut order = (0..bricks.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&idx| bricks[idx].min.z);
+
+        let mut occupied = HashSet::new();
+        let mut moved = 0;
+        for idx in order {
+            let brick = &mut bricks[idx];
+            let start = brick.min.z;
+
+            while brick.min.z > 1
+                && brick
+                    .voxels()
+                    .all(|voxel| !occupied.contains(&(voxel + Direction3::Down)))
+            {
+                *brick = brick.dropped(Axis::Z, 1);
+            }
+
+            moved += u64::from(brick.min.z != start);
+            occupied.extend(brick.voxels());
+        }
+
+        moved
+    }
+
+    #[test]
+    fn test_generate() {
+        let input = generate(60, &mut Rng::new(22));
+        let mut bricks = parser::parse(&input).unwrap();
+
+        let mut occupied = HashSet::new();
+        for voxel in bricks.iter().flat_map(|brick| brick.voxels()) {
+            assert!(occupied.insert(voxel), "Overlap at {voxel:?}");
+        }
+
+        // Removes every brick in turn and lets the others fall again
+        settle(&mut bricks);
+
+        let (mut safe, mut fallen) = (0, 0);
+        for removed in 0..bricks.len() {
+            let mut rest = bricks.clone();
+            rest.remove(removed);
+
+            let moved = settle(&mut rest);
+            safe += u64::from(moved == 0);
+            fallen += moved;
+        }
+
+        assert_eq!(solve_part1(&input).unwrap(), safe);
+        assert_eq!(solve_part2(&input).unwrap(), fallen);
+    }
+}
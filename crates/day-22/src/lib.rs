@@ -1,21 +1,53 @@
 use std::collections::VecDeque;
 
-use ahash::{AHashMap as HashMap, AHashSet as HashSet};
+use ahash::AHashSet as HashSet;
+use aoc_util::{
+    error::{Located, SolveError},
+    grid3::{Cuboid, Direction3, Grid3, HeightMap, Position},
+    random::Rng,
+    solution::{Example, Solution},
+};
 use itertools::Itertools;
-use nalgebra::{vector, Vector3};
 use rayon::prelude::*;
 
+mod generate;
 mod parser;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 22;
+    const TITLE: &'static str = "Sand Slabs";
+
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 5, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 7, solve_part2)]
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Option<String> {
+        Some(generate::generate(size, rng))
+    }
 }
 
-fn solve_part1(input: &str) -> u64 {
-    let mut bricks = parser::parse(input).unwrap();
-    let support_structure = collapse_to_support_structure(bricks.as_mut_slice());
+fn solve_part1(input: &str) -> Result<u64, SolveError> {
+    let mut bricks = parser::parse(input).located(input)?;
+    let support_structure = collapse_to_support_structure(bricks.as_mut_slice())?;
 
-    (0..bricks.len())
+    let count = (0..bricks.len())
         .filter(|&brick_idx| {
             let support = &support_structure[brick_idx];
             for &supported_idx in support.supports.iter() {
@@ -26,18 +58,16 @@ fn solve_part1(input: &str) -> u64 {
 
             true
         })
-        .count() as u64
-}
+        .count() as u64;
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+    Ok(count)
 }
 
-fn solve_part2(input: &str) -> u64 {
-    let mut bricks = parser::parse(input).unwrap();
-    let support_structure = collapse_to_support_structure(bricks.as_mut_slice());
+fn solve_part2(input: &str) -> Result<u64, SolveError> {
+    let mut bricks = parser::parse(input).located(input)?;
+    let support_structure = collapse_to_support_structure(bricks.as_mut_slice())?;
 
-    (0..bricks.len())
+    let count = (0..bricks.len())
         .filter(|&brick_idx| {
             let support = &support_structure[brick_idx];
             for &supported_idx in support.supports.iter() {
@@ -50,45 +80,73 @@ fn solve_part2(input: &str) -> u64 {
         })
         .par_bridge()
         .map(|brick_idx| count_supported_bricks(brick_idx, &support_structure))
-        .sum()
+        .sum();
+
+    Ok(count)
 }
 
-fn collapse_to_support_structure(bricks: &mut [Brick]) -> Vec<Support> {
-    bricks.sort_unstable_by_key(|brick| brick.a.z);
+/// Largest number of voxels the bricks may span, keeping the stack under
+/// 256 MiB so that bricks far apart fail instead of exhausting the memory.
+/// The real inputs span about 10 by 10 by 350 voxels.
+const MAX_VOXELS: u64 = (256 << 20) / std::mem::size_of::<Option<usize>>() as u64;
+
+/// Lets the bricks fall, sorted from the bottom, and finds which bricks
+/// support which. The stack spans the bricks from their smallest x and y, and
+/// from the ground up.
+#[tracing::instrument(skip_all)]
+fn collapse_to_support_structure(bricks: &mut [Brick]) -> Result<Vec<Support>, SolveError> {
+    let Some(bounds) = bricks.iter().copied().reduce(|bounds, brick| {
+        Cuboid::new(bounds.min.inf(&brick.min), bounds.max.sup(&brick.max))
+    }) else {
+        return Ok(Vec::new());
+    };
+
+    if bounds.min.z < 1 {
+        return Err(SolveError::unsolvable("Bricks must lie above the ground"));
+    }
+
+    let origin = Position::new(bounds.min.x, bounds.min.y, 0);
+    let size = (0..3)
+        .map(|axis| bounds.max[axis].checked_sub(origin[axis])?.checked_add(1))
+        .collect::<Option<Vec<_>>>()
+        .filter(|size| {
+            size.iter()
+                .try_fold(1u64, |voxels, &length| voxels.checked_mul(length as u64))
+                .is_some_and(|voxels| voxels <= MAX_VOXELS)
+        })
+        .ok_or_else(|| {
+            SolveError::unsolvable(format!("Bricks must span at most {MAX_VOXELS} voxels"))
+        })?;
+    let (width, depth, height) = (size[0] as usize, size[1] as usize, size[2] as usize);
+
+    bricks.sort_unstable_by_key(|brick| brick.min.z);
+
+    let mut heights = HeightMap::new(width, depth);
+    let mut stack = Grid3::filled(width, depth, height, None);
 
     let mut support_structure = vec![Support::default(); bricks.len()];
-    let mut stack = HashMap::new();
 
     for (brick_idx, brick) in bricks.iter_mut().enumerate() {
-        loop {
-            if brick.a.z <= 1 {
-                stack.extend(brick.positions().map(|position| (position, brick_idx)));
-                break;
-            }
-
-            let mut is_supported = false;
-            for support_idx in brick
-                .positions()
-                .map(|position| position - vector![0, 0, 1])
-                .filter_map(|dropped| stack.get(&dropped).copied())
-                .unique()
-            {
-                is_supported = true;
-                support_structure[brick_idx].supported_by.push(support_idx);
-                support_structure[support_idx].supports.push(brick_idx);
-            }
+        let landed = heights.land(&brick.translated(-origin));
+
+        for support_idx in landed
+            .face(Direction3::Down)
+            .voxels()
+            .filter_map(|voxel| stack.get(&(voxel + Direction3::Down)).copied().flatten())
+            .unique()
+        {
+            support_structure[brick_idx].supported_by.push(support_idx);
+            support_structure[support_idx].supports.push(brick_idx);
+        }
 
-            if is_supported {
-                stack.extend(brick.positions().map(|position| (position, brick_idx)));
-                break;
-            } else {
-                brick.a.z -= 1;
-                brick.b.z -= 1;
-            }
+        for voxel in landed.voxels() {
+            stack.put(&voxel, Some(brick_idx));
         }
+
+        *brick = landed.translated(origin);
     }
 
-    support_structure
+    Ok(support_structure)
 }
 
 fn count_supported_bricks(start: usize, support_structure: &[Support]) -> u64 {
@@ -113,60 +171,7 @@ fn count_supported_bricks(start: usize, support_structure: &[Support]) -> u64 {
     visited.len() as u64 - 1
 }
 
-type Position = Vector3<i64>;
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Brick {
-    a: Position,
-    b: Position,
-    orientation: Orientation,
-}
-
-impl Brick {
-    pub fn new(a: Position, b: Position) -> Self {
-        assert!(a.x <= b.x);
-        assert!(a.y <= b.y);
-        assert!(a.z <= b.z);
-
-        let orientation = if a.x != b.x {
-            Orientation::X
-        } else if a.y != b.y {
-            Orientation::Y
-        } else {
-            Orientation::Z
-        };
-
-        Self { a, b, orientation }
-    }
-
-    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
-        let a = self.a;
-        let index = match self.orientation {
-            Orientation::X => 0,
-            Orientation::Y => 1,
-            Orientation::Z => 2,
-        };
-
-        let position = move |coord| {
-            let mut position = a;
-            position[index] = coord;
-            position
-        };
-
-        match self.orientation {
-            Orientation::X => (self.a.x..=self.b.x).map(position),
-            Orientation::Y => (self.a.y..=self.b.y).map(position),
-            Orientation::Z => (self.a.z..=self.b.z).map(position),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Orientation {
-    X,
-    Y,
-    Z,
-}
+type Brick = Cuboid;
 
 #[derive(Default, Debug, Clone)]
 struct Support {
@@ -174,11 +179,7 @@ struct Support {
     supports: Vec<usize>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "1,0,1~1,2,1
+const EXAMPLE_INPUT: &str = "1,0,1~1,2,1
 0,0,2~2,0,2
 0,2,3~2,2,3
 0,0,4~0,2,4
@@ -187,15 +188,54 @@ mod tests {
 1,1,8~1,1,9
 ";
 
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 5);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 7);
     }
+
+    #[test]
+    fn test_negative_coordinates() {
+        // The example moved 5 to the west and 3 to the north
+        let input = "-4,-3,1~-4,-1,1
+-5,-3,2~-3,-3,2
+-5,-1,3~-3,-1,3
+-5,-3,4~-5,-1,4
+-3,-3,5~-3,-1,5
+-5,-2,6~-3,-2,6
+-4,-2,8~-4,-2,9
+";
+
+        assert_eq!(solve_part1(input).unwrap(), 5);
+        assert_eq!(solve_part2(input).unwrap(), 7);
+    }
+
+    #[rstest]
+    #[case("0,0,0~0,0,1\n", "Bricks must lie above the ground")]
+    #[case(
+        "0,0,1~0,0,1\n100000,100000,1~100000,100000,1\n",
+        "Bricks must span at most 16777216 voxels"
+    )]
+    // One column more than the cap allows, over the ground and the first layer
+    #[case(
+        "0,0,1~0,0,1\n8388608,0,1~8388608,0,1\n",
+        "Bricks must span at most 16777216 voxels"
+    )]
+    fn test_invalid_bricks(#[case] input: &str, #[case] message: &str) {
+        let error = solve_part1(input).unwrap_err();
+        assert!(matches!(error, SolveError::Unsolvable(_)), "{error:?}");
+        assert_eq!(error.to_string(), message);
+    }
 }
@@ -8,8 +8,11 @@ use nom::{
 };
 use nom_supreme::final_parser::final_parser;
 
-use crate::{Brick, Position};
+use aoc_util::grid3::Position;
 
+use crate::Brick;
+
+#[tracing::instrument(skip_all)]
 pub fn parse(input: &str) -> Result<Vec<Brick>, nom::error::Error<&str>> {
     final_parser(parser)(input)
 }
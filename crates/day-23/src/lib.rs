@@ -3,16 +3,40 @@ use std::collections::VecDeque;
 use ahash::{AHashMap as HashMap, AHashSet as HashSet};
 use aoc_util::{
     cache::Cache,
-    grid::{Direction, Grid, Position, TileChar},
+    error::SolveError,
+    grid::{Direction, Grid, Position, ReadGrid, TileChar},
+    solution::{Example, Solution},
 };
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 23;
+    const TITLE: &'static str = "A Long Walk";
+
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 94, solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 154, solve_part2)]
+    }
 }
 
-fn solve_part1(input: &str) -> u64 {
-    let map = parse(input);
+fn solve_part1(input: &str) -> Result<u64, SolveError> {
+    let map = parse(input)?;
     let start = Position::new(1, 0);
     let graph = build_trail_graph1(start, &map);
 
@@ -41,9 +65,10 @@ fn solve_part1(input: &str) -> u64 {
     }
 
     let mut cache = HashMap::new();
-    max_length(start, &graph, &mut cache)
+    Ok(max_length(start, &graph, &mut cache))
 }
 
+#[tracing::instrument(skip_all)]
 fn build_trail_graph1(start: Position, map: &Map) -> HashMap<Position, Vec<(Position, u64)>> {
     let mut graph = HashMap::new();
 
@@ -55,16 +80,14 @@ fn build_trail_graph1(start: Position, map: &Map) -> HashMap<Position, Vec<(Posi
             continue;
         }
 
-        let neighbors = Direction::ALL
-            .into_iter()
-            .filter(|&d| {
-                let neighbor = position + d;
-                match map.get(&neighbor) {
-                    None | Some(Tile::Forest) => false,
-                    Some(Tile::Path) => true,
-                    Some(Tile::Slope(slope)) => *slope == d,
-                }
+        let neighbors = map
+            .adjacent(&position, Direction::ALL)
+            .filter(|&(d, _, tile)| match tile {
+                Tile::Forest => false,
+                Tile::Path => true,
+                Tile::Slope(slope) => *slope == d,
             })
+            .map(|(d, _, _)| d)
             .collect_vec();
 
         for (neighbor, distance) in neighbors
@@ -82,12 +105,8 @@ fn build_trail_graph1(start: Position, map: &Map) -> HashMap<Position, Vec<(Posi
     graph
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> u64 {
-    let map = parse(input);
+fn solve_part2(input: &str) -> Result<u64, SolveError> {
+    let map = parse(input)?;
     let start = Position::new(1, 0);
     let end = Position::new(map.width() - 2, map.height() - 1);
     let mut graph = build_trail_graph2(start, &map);
@@ -122,9 +141,11 @@ fn solve_part2(input: &str) -> u64 {
     }
 
     let mut visited = HashSet::new();
-    max_length(start, end, &mut visited, &graph).unwrap_or(0)
+    max_length(start, end, &mut visited, &graph)
+        .ok_or_else(|| SolveError::unsolvable("No path to the exit"))
 }
 
+#[tracing::instrument(skip_all)]
 fn build_trail_graph2(start: Position, map: &Map) -> HashMap<Position, Vec<(Position, u64)>> {
     let mut graph = HashMap::new();
 
@@ -136,16 +157,14 @@ fn build_trail_graph2(start: Position, map: &Map) -> HashMap<Position, Vec<(Posi
             continue;
         }
 
-        let neighbors = Direction::ALL
-            .into_iter()
-            .filter(|&d| {
-                let neighbor = position + d;
-                match map.get(&neighbor) {
-                    None | Some(Tile::Forest) => false,
-                    Some(Tile::Path) => true,
-                    Some(Tile::Slope(slope)) => *slope == d,
-                }
+        let neighbors = map
+            .adjacent(&position, Direction::ALL)
+            .filter(|&(d, _, tile)| match tile {
+                Tile::Forest => false,
+                Tile::Path => true,
+                Tile::Slope(slope) => *slope == d,
             })
+            .map(|(d, _, _)| d)
             .collect_vec();
 
         for (neighbor, distance) in neighbors
@@ -166,6 +185,7 @@ fn build_trail_graph2(start: Position, map: &Map) -> HashMap<Position, Vec<(Posi
     graph
 }
 
+#[tracing::instrument(skip_all)]
 fn prune_border_edges(end: Position, graph: &mut HashMap<Position, Vec<(Position, u64)>>) {
     let mut visited = HashSet::from([end]);
     let mut queue = VecDeque::from([end]);
@@ -196,11 +216,13 @@ fn walk_along_path(mut position: Position, mut direction: Direction, map: &Map)
     position += direction;
 
     loop {
-        let mut iter = [direction.turn_left(), direction, direction.turn_right()]
-            .into_iter()
-            .filter_map(|d| map.get(&(position + d)).map(|t| (d, t)))
-            .filter(|(_, t)| !matches!(t, Tile::Forest))
-            .map(|(d, _)| d);
+        let mut iter = map
+            .adjacent(
+                &position,
+                [direction.turn_left(), direction, direction.turn_right()],
+            )
+            .filter(|(_, _, t)| !matches!(t, Tile::Forest))
+            .map(|(d, _, _)| d);
         let next = iter.next();
         let overflow = iter.next();
 
@@ -236,32 +258,18 @@ impl TileChar for Tile {
     }
 }
 
-fn parse(input: &str) -> Map {
-    let mut width = 0;
-    let mut height = 0;
-    let mut tiles = Vec::new();
-
-    for (y, line) in input.lines().enumerate() {
-        if y == 0 {
-            width = line.len();
-        }
-        height += 1;
-
-        for c in line.chars() {
-            let tile = match c {
-                '.' => Tile::Path,
-                '^' => Tile::Slope(Direction::Up),
-                'v' => Tile::Slope(Direction::Down),
-                '<' => Tile::Slope(Direction::Left),
-                '>' => Tile::Slope(Direction::Right),
-                _ => Tile::Forest,
-            };
-
-            tiles.push(tile);
-        }
-    }
-
-    Map::new(width, height, tiles)
+#[tracing::instrument(skip_all)]
+fn parse(input: &str) -> Result<Map, SolveError> {
+    Map::parse(input, |c, _| {
+        Ok::<_, String>(match c {
+            '.' => Tile::Path,
+            '^' => Tile::Slope(Direction::Up),
+            'v' => Tile::Slope(Direction::Down),
+            '<' => Tile::Slope(Direction::Left),
+            '>' => Tile::Slope(Direction::Right),
+            _ => Tile::Forest,
+        })
+    })
 }
 
 #[allow(dead_code)]
@@ -287,11 +295,7 @@ fn print_graph(graph: &HashMap<Position, Vec<(Position, u64)>>) {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "#.#####################
+const EXAMPLE_INPUT: &str = "#.#####################
 #.......#########...###
 #######.#########.#.###
 ###.....#.>.>.###.#.###
@@ -316,15 +320,19 @@ mod tests {
 #####################.#
 ";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 94);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 154);
     }
 }
@@ -1,22 +1,54 @@
-use std::ops::RangeBounds;
+use std::{ops::RangeBounds, str::FromStr};
 
+use aoc_util::{
+    error::SolveError,
+    solution::{Example, Solution},
+};
 use itertools::Itertools;
-use nalgebra::{vector, Matrix3, Vector2, Vector3};
+use nalgebra::{vector, Matrix3, Scalar, Vector2, Vector3};
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input, 200000000000000..=400000000000000)
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 24;
+    const TITLE: &'static str = "Never Tell Me The Odds";
+
+    type Answer1 = usize;
+    type Answer2 = i64;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input, 200000000000000..=400000000000000)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![
+            Example::new(EXAMPLE_INPUT, 2, |input| solve_part1(input, 7..=27))
+                .with_parameters(&[("min", 7), ("max", 27)]),
+        ]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, 47, solve_part2)]
+    }
 }
 
-fn solve_part1<R>(input: &str, bounds: R) -> usize
+fn solve_part1<R>(input: &str, bounds: R) -> Result<usize, SolveError>
 where
     R: RangeBounds<i64>,
 {
-    input
-        .lines()
-        .map(parse_int)
+    let hailstones: Vec<Hailstone<i64>> = parse(input)?;
+
+    let count = hailstones
+        .iter()
         .tuple_combinations()
         .filter(|(a, b)| intersects_in_bounds(a, b, &bounds))
-        .count()
+        .count();
+
+    Ok(count)
 }
 
 fn intersects_in_bounds<R>(a: &Hailstone<i64>, b: &Hailstone<i64>, bounds: &R) -> bool
@@ -72,14 +104,11 @@ fn intersection_point(
     Some(vector![x as i64, y as i64])
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
-}
+fn solve_part2(input: &str) -> Result<i64, SolveError> {
+    let hailstones: Vec<Hailstone<f64>> = parse(input)?;
 
-fn solve_part2(input: &str) -> i64 {
-    let result = input
-        .lines()
-        .map(parse_float)
+    let result = hailstones
+        .iter()
         .tuple_combinations()
         .filter_map(|(a, b, c)| {
             let m = Matrix3::from_rows(&[
@@ -105,10 +134,10 @@ fn solve_part2(input: &str) -> i64 {
             Some(inv_m * d)
         })
         .next()
-        .unwrap();
+        .ok_or_else(|| SolveError::unsolvable("No three independent hailstones"))?;
 
     // I got lucky and f64 had enough precision for this to be correct with my input
-    (result.x + result.y + result.z) as i64
+    Ok((result.x + result.y + result.z) as i64)
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -123,54 +152,58 @@ impl<T> Hailstone<T> {
     }
 }
 
-fn parse_int(input: &str) -> Hailstone<i64> {
-    fn parse_vector(input: &str) -> Vector3<i64> {
-        let (x, y, z) = input
-            .split(',')
-            .map(|s| s.trim().parse::<i64>().unwrap())
-            .next_tuple()
-            .unwrap();
-        vector![x, y, z]
-    }
-
-    let (position, velocity) = input.split_once('@').unwrap();
-    Hailstone::new(parse_vector(position), parse_vector(velocity))
-}
-
-fn parse_float(input: &str) -> Hailstone<f64> {
-    fn parse_vector(input: &str) -> Vector3<f64> {
-        let (x, y, z) = input
+#[tracing::instrument(skip_all)]
+fn parse<T>(input: &str) -> Result<Vec<Hailstone<T>>, SolveError>
+where
+    T: Scalar + FromStr,
+{
+    let parse_vector = |vector: &str| -> Result<Vector3<T>, SolveError> {
+        let (x, y, z) = vector
             .split(',')
-            .map(|s| s.trim().parse::<f64>().unwrap())
-            .next_tuple()
-            .unwrap();
-        vector![x, y, z]
-    }
+            .map(|s| {
+                s.trim()
+                    .parse::<T>()
+                    .map_err(|_| SolveError::at(input, s, "Expected a number"))
+            })
+            .collect_tuple()
+            .ok_or_else(|| SolveError::at(input, vector, "Expected three coordinates"))?;
+        Ok(vector![x?, y?, z?])
+    };
 
-    let (position, velocity) = input.split_once('@').unwrap();
-    Hailstone::new(parse_vector(position), parse_vector(velocity))
+    input
+        .lines()
+        .map(|line| {
+            let (position, velocity) = line
+                .split_once('@')
+                .ok_or_else(|| SolveError::at(input, line, "Expected `<position> @ <velocity>`"))?;
+            Ok(Hailstone::new(
+                parse_vector(position)?,
+                parse_vector(velocity)?,
+            ))
+        })
+        .collect()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "19, 13, 30 @ -2,  1, -2
+const EXAMPLE_INPUT: &str = "19, 13, 30 @ -2,  1, -2
 18, 19, 22 @ -1, -1, -2
 20, 25, 34 @ -2, -2, -4
 12, 31, 28 @ -1, -2, -1
 20, 19, 15 @  1, -5, -3
 ";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT, 7..=27);
+        let solution = solve_part1(EXAMPLE_INPUT, 7..=27).unwrap();
         assert_eq!(solution, 2);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 47);
     }
 }
@@ -1,27 +1,47 @@
 use ahash::AHashMap as HashMap;
+use aoc_util::{
+    error::{Located, SolveError},
+    solution::{Example, Solution},
+};
 
 mod parser;
 
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
-}
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 25;
+    const TITLE: &'static str = "Snowverload";
 
-fn solve_part1(input: &str) -> usize {
-    let graph = parser::parse(input).unwrap();
-    assert!(!graph.is_empty());
+    type Answer1 = usize;
+    type Answer2 = ();
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
 
-    let (minimum_cut, partition) =
-        stoer_wagner::minimum_cut(&graph, 3).expect("Graph should have a cut");
-    assert_eq!(minimum_cut, 3);
-    partition.len() * (graph.len() - partition.len())
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, 54, solve_part1)]
+    }
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+fn solve_part1(input: &str) -> Result<usize, SolveError> {
+    let graph = parser::parse(input).located(input)?;
+    if graph.is_empty() {
+        return Err(SolveError::unsolvable("Empty graph"));
+    }
+
+    let (_, partition) = stoer_wagner::minimum_cut(&graph, 3)
+        .filter(|(minimum_cut, _)| *minimum_cut == 3)
+        .ok_or_else(|| SolveError::unsolvable("Graph should have a cut of three wires"))?;
+    Ok(partition.len() * (graph.len() - partition.len()))
 }
 
-fn solve_part2(_input: &str) -> &'static str {
-    "()"
+fn solve_part2(_input: &str) -> Result<(), SolveError> {
+    Ok(())
 }
 
 #[derive(Default, Clone)]
@@ -88,24 +108,7 @@ mod stoer_wagner {
 
     use super::*;
 
-    #[derive(Clone, PartialEq, Eq)]
-    struct Edge {
-        neighbor: String,
-        weight: usize,
-    }
-
-    impl PartialOrd for Edge {
-        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-            Some(self.cmp(other))
-        }
-    }
-
-    impl Ord for Edge {
-        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-            self.weight.cmp(&other.weight)
-        }
-    }
-
+    #[tracing::instrument(skip_all)]
     pub fn minimum_cut(graph: &Graph, target_cut: i64) -> Option<(i64, Vec<usize>)> {
         let mut matrix = graph.adjacency_matrix.clone();
         let mut collapsed = (0..graph.len()).map(|index| vec![index]).collect_vec();
@@ -131,6 +134,7 @@ mod stoer_wagner {
         minimum
     }
 
+    #[allow(clippy::needless_range_loop)]
     fn minimum_cut_phase(
         graph: &Graph,
         matrix: &mut [Vec<i64>],
@@ -206,11 +210,7 @@ mod stoer_wagner {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const TEST_INPUT: &str = "jqt: rhn xhk nvd
+const EXAMPLE_INPUT: &str = "jqt: rhn xhk nvd
 rsh: frs pzl lsr
 xhk: hfx
 cmg: qnr nvd lhk bvb
@@ -225,15 +225,21 @@ rzs: qnr cmg lsr rsh
 frs: qnr lhk lsr
 ";
 
+#[cfg(test)]
+mod tests {
+    use aoc_util::answer::Answer;
+
+    use super::*;
+
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, 54);
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
-        assert_eq!(solution, "TODO");
+        let solution = solve_part2(EXAMPLE_INPUT).map(Answer::from).unwrap();
+        assert_eq!(solution, Answer::Unit);
     }
 }
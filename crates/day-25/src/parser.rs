@@ -9,7 +9,8 @@ use nom_supreme::final_parser::final_parser;
 
 use crate::Graph;
 
-pub fn parse(input: &str) -> Result<Graph, nom::error::Error<&str>> {
+#[tracing::instrument(skip_all)]
+pub fn parse(input: &str) -> Result<Graph<'_>, nom::error::Error<&str>> {
     let adjacency = final_parser(parser)(input)?;
 
     Ok(Graph::from_iter(adjacency))
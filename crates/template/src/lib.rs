@@ -1,34 +1,57 @@
-pub fn part1(input: &str) -> impl std::fmt::Display {
-    solve_part1(input)
-}
+use aoc_util::{
+    error::SolveError,
+    solution::{Example, Solution},
+};
+
+pub struct Day;
+
+impl Solution for Day {
+    const ID: u32 = 0;
+    const TITLE: &'static str = "Template";
+
+    type Answer1 = &'static str;
+    type Answer2 = &'static str;
 
-fn solve_part1(_input: &str) -> &'static str {
-    "TODO"
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError> {
+        solve_part2(input)
+    }
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        vec![Example::new(EXAMPLE_INPUT, "TODO", solve_part1)]
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        vec![Example::new(EXAMPLE_INPUT, "TODO", solve_part2)]
+    }
 }
 
-pub fn part2(input: &str) -> impl std::fmt::Display {
-    solve_part2(input)
+fn solve_part1(_input: &str) -> Result<&'static str, SolveError> {
+    Ok("TODO")
 }
 
-fn solve_part2(_input: &str) -> &'static str {
-    "TODO"
+fn solve_part2(_input: &str) -> Result<&'static str, SolveError> {
+    Ok("TODO")
 }
 
+const EXAMPLE_INPUT: &str = "";
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "";
-
     #[test]
     fn test_part1() {
-        let solution = solve_part1(TEST_INPUT);
+        let solution = solve_part1(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, "TODO");
     }
 
     #[test]
     fn test_part2() {
-        let solution = solve_part2(TEST_INPUT);
+        let solution = solve_part2(EXAMPLE_INPUT).unwrap();
         assert_eq!(solution, "TODO");
     }
 }
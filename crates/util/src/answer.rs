@@ -0,0 +1,113 @@
+use std::fmt::{Display, Formatter};
+
+/// Answer to a puzzle part. Integers are normalized, so the same number
+/// always ends up in the same variant regardless of the type it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Integer(i64),
+    /// An integer outside of the range of `i64`
+    BigInteger(i128),
+    String(String),
+    /// A part without an answer, such as the second part of the last day
+    Unit,
+}
+
+impl Answer {
+    /// Representation of [`Answer::Unit`] in text.
+    pub const UNIT: &'static str = "-";
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::BigInteger(value) => write!(f, "{value}"),
+            Self::String(value) => write!(f, "{value}"),
+            Self::Unit => write!(f, "{}", Self::UNIT),
+        }
+    }
+}
+
+/// Parses integers into the integer variants, [`Answer::UNIT`] into
+/// [`Answer::Unit`] and anything else into a string answer.
+impl std::str::FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == Self::UNIT {
+            return Ok(Self::Unit);
+        }
+
+        Ok(match s.parse::<i128>() {
+            Ok(value) => value.into(),
+            Err(_) => Self::String(s.to_string()),
+        })
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(value: i128) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => Self::Integer(value),
+            Err(_) => Self::BigInteger(value),
+        }
+    }
+}
+
+macro_rules! from_integer {
+    ($($integer:ty),*) => {
+        $(
+            impl From<$integer> for Answer {
+                fn from(value: $integer) -> Self {
+                    i128::from(value).into()
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(u32, i32, u64, i64);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        (value as u64).into()
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<()> for Answer {
+    fn from(_: ()) -> Self {
+        Self::Unit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integers_are_normalized() {
+        assert_eq!(Answer::from(42u32), Answer::from(42usize));
+        assert_eq!(Answer::from(42u64), Answer::Integer(42));
+        assert_eq!(Answer::from(u64::MAX), Answer::BigInteger(u64::MAX as i128));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("-17".parse(), Ok(Answer::Integer(-17)));
+        assert_eq!("18446744073709551615".parse(), Ok(Answer::from(u64::MAX)));
+        assert_eq!("abc".parse(), Ok(Answer::from("abc")));
+        assert_eq!(Answer::UNIT.parse(), Ok(Answer::Unit));
+    }
+}
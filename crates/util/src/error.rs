@@ -0,0 +1,95 @@
+use nom::{bytes::complete::take, error::ErrorKind};
+use nom_locate::LocatedSpan;
+
+/// Input span that tracks its line and column.
+pub type Span<'a> = LocatedSpan<&'a str>;
+
+/// Why a part could not be solved.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SolveError {
+    /// The input is malformed at the given position, with 1-based line and
+    /// column.
+    #[error("{message} at line {line}, column {column}")]
+    Parse {
+        line: u32,
+        column: usize,
+        message: String,
+    },
+    /// The input is well-formed, but does not have the shape the solution
+    /// relies on.
+    #[error("{0}")]
+    Unsolvable(String),
+}
+
+impl SolveError {
+    /// Parse error at the start of `position`.
+    pub fn parse(position: Span<'_>, message: impl Into<String>) -> Self {
+        Self::Parse {
+            line: position.location_line(),
+            column: position.get_utf8_column(),
+            message: message.into(),
+        }
+    }
+
+    /// Parse error at the start of `fragment`, which must be a slice of
+    /// `input`.
+    pub fn at(input: &str, fragment: &str, message: impl Into<String>) -> Self {
+        let offset = (fragment.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|offset| *offset <= input.len())
+            .expect("Fragment is not part of the input");
+
+        let (position, _) = take::<_, _, nom::error::Error<Span<'_>>>(offset)(Span::new(input))
+            .expect("Offset is within the input");
+
+        Self::parse(position, message)
+    }
+
+    pub fn unsolvable(message: impl Into<String>) -> Self {
+        Self::Unsolvable(message.into())
+    }
+}
+
+impl From<nom::error::Error<Span<'_>>> for SolveError {
+    fn from(value: nom::error::Error<Span<'_>>) -> Self {
+        Self::parse(value.input, describe(value.code))
+    }
+}
+
+fn describe(code: ErrorKind) -> String {
+    match code {
+        ErrorKind::Eof => "Unexpected trailing input".to_string(),
+        code => format!("Unexpected input, expected {}", code.description()),
+    }
+}
+
+/// Locates the errors of parsers that work on plain string slices in the
+/// input they were given.
+pub trait Located<T> {
+    fn located(self, input: &str) -> Result<T, SolveError>;
+}
+
+impl<T> Located<T> for Result<T, nom::error::Error<&str>> {
+    fn located(self, input: &str) -> Result<T, SolveError> {
+        self.map_err(|error| SolveError::at(input, error.input, describe(error.code)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_at() {
+        let input = "Game 1: 3 blue\nGame 2: 4 grin\n";
+        let error = SolveError::at(input, &input[24..], "Unknown colour");
+        assert_eq!(
+            error,
+            SolveError::Parse {
+                line: 2,
+                column: 10,
+                message: "Unknown colour".to_string()
+            }
+        );
+    }
+}
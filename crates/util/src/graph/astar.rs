@@ -66,7 +66,7 @@ where
 
             if visited
                 .get(&neighbor)
-                .map_or(true, |&(_, cost)| neighbor_cost < cost)
+                .is_none_or(|&(_, cost)| neighbor_cost < cost)
             {
                 // This path to neighbor is better than any previous one. Record it!
                 visited.insert(neighbor, (current, neighbor_cost));
@@ -1,5 +1,8 @@
+use ahash::AHashMap as HashMap;
 use nalgebra::Vector2;
 
+use crate::error::SolveError;
+
 pub type Position = Vector2<i64>;
 
 impl std::ops::AddAssign<Direction> for Position {
@@ -141,6 +144,93 @@ impl<T> Grid<T> {
         }
     }
 
+    /// Parses a grid with one character per tile and one line per row,
+    /// turning every character into a tile with `tile`. Rows of different
+    /// lengths and errors returned by `tile` are reported at their line and
+    /// column in `input`.
+    pub fn parse<F, E>(input: &str, tile: F) -> Result<Self, SolveError>
+    where
+        F: FnMut(char, Position) -> Result<T, E>,
+        E: Into<String>,
+    {
+        Self::parse_rows(input, input.lines(), None, tile).map(|(grid, _)| grid)
+    }
+
+    /// Like [`Grid::parse`], for a grid made of some of the lines of `input`,
+    /// such as one of several grids separated by blank lines. The `lines`
+    /// must be slices of `input`, so errors are reported at their position in
+    /// the whole input.
+    pub fn parse_lines<'a, F, E>(
+        input: &str,
+        lines: impl IntoIterator<Item = &'a str>,
+        tile: F,
+    ) -> Result<Self, SolveError>
+    where
+        F: FnMut(char, Position) -> Result<T, E>,
+        E: Into<String>,
+    {
+        Self::parse_rows(input, lines, None, tile).map(|(grid, _)| grid)
+    }
+
+    /// Like [`Grid::parse`], also collecting the positions of `marker`, such
+    /// as a start tile, in reading order. `tile` still decides which tile
+    /// lies under a marker.
+    pub fn parse_marked<F, E>(
+        input: &str,
+        marker: char,
+        tile: F,
+    ) -> Result<(Self, Vec<Position>), SolveError>
+    where
+        F: FnMut(char, Position) -> Result<T, E>,
+        E: Into<String>,
+    {
+        Self::parse_rows(input, input.lines(), Some(marker), tile)
+    }
+
+    fn parse_rows<'a, F, E>(
+        input: &str,
+        lines: impl IntoIterator<Item = &'a str>,
+        marker: Option<char>,
+        mut tile: F,
+    ) -> Result<(Self, Vec<Position>), SolveError>
+    where
+        F: FnMut(char, Position) -> Result<T, E>,
+        E: Into<String>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut entries = Vec::new();
+        let mut markers = Vec::new();
+
+        for (y, line) in lines.into_iter().enumerate() {
+            let mut length = 0;
+            for (offset, c) in line.char_indices() {
+                let position = Position::new(length as i64, y as i64);
+                if Some(c) == marker {
+                    markers.push(position);
+                }
+
+                let entry = tile(c, position)
+                    .map_err(|message| SolveError::at(input, &line[offset..], message))?;
+                entries.push(entry);
+                length += 1;
+            }
+
+            let width = *width.get_or_insert(length);
+            if length != width {
+                return Err(SolveError::at(
+                    input,
+                    line,
+                    format!("Expected {width} tiles as in the first row, found {length}"),
+                ));
+            }
+
+            height += 1;
+        }
+
+        Ok((Self::new(width.unwrap_or(0), height, entries), markers))
+    }
+
     pub fn is_empty(&self) -> bool {
         self.entries.is_empty()
     }
@@ -177,6 +267,11 @@ impl<T> Grid<T> {
         self.index(position).and_then(|idx| self.entries.get(idx))
     }
 
+    pub fn get_mut(&mut self, position: &Position) -> Option<&mut T> {
+        self.index(position)
+            .and_then(|idx| self.entries.get_mut(idx))
+    }
+
     pub fn put(&mut self, position: &Position, tile: T) {
         if let Some(index) = self.index(position) {
             self.entries[index] = tile;
@@ -186,6 +281,451 @@ impl<T> Grid<T> {
     pub fn iter(&self) -> impl Iterator<Item = &T> {
         self.entries.iter()
     }
+
+    /// Tiles with their positions, in reading order.
+    pub fn iter_positions(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(idx, tile)| (self.position(idx), tile))
+    }
+
+    /// Neighbouring tiles, wrapping around the edges as if the grid were
+    /// repeated in every direction. Positions are wrapped into the grid.
+    pub fn wrapping_neighbors(
+        &self,
+        position: &Position,
+        neighborhood: Neighborhood,
+    ) -> impl Iterator<Item = (Position, &T)> {
+        let position = *position;
+        neighborhood
+            .offsets()
+            .map(move |offset| self.wrap(&(position + offset)))
+            .filter_map(|neighbor| self.get(&neighbor).map(|tile| (neighbor, tile)))
+    }
+
+    /// The position within the grid that `position` maps to when the grid
+    /// repeats in every direction.
+    pub fn wrap(&self, position: &Position) -> Position {
+        Position::new(
+            position.x.rem_euclid(self.width),
+            position.y.rem_euclid(self.height),
+        )
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        let width = self.width as usize;
+        &self.entries[y * width..(y + 1) * width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // Chunks of zero length are not allowed
+        self.entries.chunks(self.width.max(1) as usize)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width as usize, "Column {x} outside the grid");
+        self.entries.iter().skip(x).step_by(self.width as usize)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width as usize).map(|x| self.column(x))
+    }
+
+    /// View of the `width` by `height` tiles starting at `origin`, if they
+    /// all lie within the grid.
+    pub fn window(&self, origin: &Position, width: usize, height: usize) -> Option<Window<'_, T>> {
+        let (width, height) = (width as i64, height as i64);
+        let within = origin.x >= 0
+            && origin.y >= 0
+            && origin.x + width <= self.width
+            && origin.y + height <= self.height;
+
+        within.then_some(Window {
+            grid: self,
+            origin: *origin,
+            width,
+            height,
+        })
+    }
+
+    /// Slides every tile for which `moves` holds towards `direction` as far
+    /// as it goes, like rocks on a tilted platform. Sliding tiles stop at the
+    /// edge, at tiles for which `blocks` holds and at tiles that already
+    /// stopped, and swap places with the other tiles they pass.
+    pub fn compact(
+        &mut self,
+        direction: Direction,
+        moves: impl Fn(&T) -> bool,
+        blocks: impl Fn(&T) -> bool,
+    ) {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let (lines, length) = match direction.orientation() {
+            Orientation::Vertical => (width, height),
+            Orientation::Horizontal => (height, width),
+        };
+
+        // Index of the tile `step` tiles from the edge `direction` points to
+        let index = |line: usize, step: usize| match direction {
+            Direction::Up => step * width + line,
+            Direction::Down => (height - 1 - step) * width + line,
+            Direction::Left => line * width + step,
+            Direction::Right => line * width + width - 1 - step,
+        };
+
+        for line in 0..lines {
+            let mut target = 0;
+            for step in 0..length {
+                let idx = index(line, step);
+                if blocks(&self.entries[idx]) {
+                    target = step + 1;
+                } else if moves(&self.entries[idx]) {
+                    self.entries.swap(index(line, target), idx);
+                    target += 1;
+                }
+            }
+        }
+    }
+
+    /// Every `width` by `height` window of the grid, with their origins in
+    /// reading order.
+    pub fn windows(&self, width: usize, height: usize) -> impl Iterator<Item = Window<'_, T>> {
+        let origins_y = 0..(self.height - height as i64 + 1).max(0);
+        let origins_x = 0..(self.width - width as i64 + 1).max(0);
+
+        origins_y
+            .flat_map(move |y| origins_x.clone().map(move |x| Position::new(x, y)))
+            .filter_map(move |origin| self.window(&origin, width, height))
+    }
+}
+
+/// Read access to the tiles of a grid by position, shared by finite grids and
+/// their windows, tiled grids and sparse grids so searches can run on any of
+/// them. Tiled and sparse grids reach every position, so their neighbours are
+/// never cut off at an edge.
+pub trait ReadGrid {
+    type Tile;
+
+    /// Tile at `position`, or `None` if the grid does not reach it.
+    fn get(&self, position: &Position) -> Option<&Self::Tile>;
+
+    /// Neighbouring tiles within the grid, with their positions.
+    fn neighbors(
+        &self,
+        position: &Position,
+        neighborhood: Neighborhood,
+    ) -> impl Iterator<Item = (Position, &Self::Tile)> + use<'_, Self> {
+        let position = *position;
+        neighborhood
+            .offsets()
+            .map(move |offset| position + offset)
+            .filter_map(|neighbor| self.get(&neighbor).map(|tile| (neighbor, tile)))
+    }
+
+    /// Tiles one step from `position` in each of `directions` that lie
+    /// within the grid.
+    fn adjacent<D>(
+        &self,
+        position: &Position,
+        directions: D,
+    ) -> impl Iterator<Item = (Direction, Position, &Self::Tile)> + use<'_, Self, D>
+    where
+        D: IntoIterator<Item = Direction>,
+    {
+        let position = *position;
+        directions.into_iter().filter_map(move |direction| {
+            let neighbor = position + direction;
+            self.get(&neighbor).map(|tile| (direction, neighbor, tile))
+        })
+    }
+}
+
+impl<T> ReadGrid for Grid<T> {
+    type Tile = T;
+
+    fn get(&self, position: &Position) -> Option<&T> {
+        Grid::get(self, position)
+    }
+}
+
+/// A finite [`Grid`] repeated infinitely in every direction.
+#[derive(Clone)]
+pub struct TiledGrid<T> {
+    grid: Grid<T>,
+}
+
+impl<T> TiledGrid<T> {
+    pub fn new(grid: Grid<T>) -> Self {
+        assert!(!grid.is_empty(), "Cannot tile an empty grid");
+        Self { grid }
+    }
+
+    /// The repeated grid.
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    /// Which copy of the grid `position` lies in, the original being at
+    /// (0, 0).
+    pub fn copy_of(&self, position: &Position) -> Position {
+        Position::new(
+            position.x.div_euclid(self.grid.width),
+            position.y.div_euclid(self.grid.height),
+        )
+    }
+}
+
+impl<T> ReadGrid for TiledGrid<T> {
+    type Tile = T;
+
+    fn get(&self, position: &Position) -> Option<&T> {
+        self.grid.get(&self.grid.wrap(position))
+    }
+}
+
+/// Unbounded grid that only stores the tiles that were put into it, every
+/// other position holding a default tile.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    tiles: HashMap<Position, T>,
+    default: T,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> Self {
+        Self {
+            tiles: HashMap::new(),
+            default,
+        }
+    }
+
+    /// Number of stored tiles.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    pub fn put(&mut self, position: &Position, tile: T) {
+        self.tiles.insert(*position, tile);
+    }
+
+    /// Removes the tile at `position`, which then holds the default tile
+    /// again.
+    pub fn remove(&mut self, position: &Position) -> Option<T> {
+        self.tiles.remove(position)
+    }
+
+    /// Stored tiles with their positions, in no particular order.
+    pub fn iter_positions(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.tiles.iter().map(|(position, tile)| (*position, tile))
+    }
+
+    /// Smallest and largest corner of the rectangle around the stored tiles.
+    pub fn bounds(&self) -> Option<(Position, Position)> {
+        let mut positions = self.tiles.keys();
+        let first = *positions.next()?;
+
+        Some(positions.fold((first, first), |(min, max), position| {
+            (min.inf(position), max.sup(position))
+        }))
+    }
+}
+
+impl<T> SparseGrid<T>
+where
+    T: Clone,
+{
+    /// Mutable tile at `position`, storing a copy of the default tile first
+    /// if there is none.
+    pub fn get_mut(&mut self, position: &Position) -> &mut T {
+        self.tiles
+            .entry(*position)
+            .or_insert_with(|| self.default.clone())
+    }
+
+    /// The stored tiles within their bounds as a finite grid, with the
+    /// origin of the grid.
+    pub fn to_grid(&self) -> Option<(Grid<T>, Position)> {
+        let (min, max) = self.bounds()?;
+        let (width, height) = ((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize);
+
+        let entries = (min.y..=max.y)
+            .flat_map(|y| (min.x..=max.x).map(move |x| Position::new(x, y)))
+            .map(|position| self.tiles.get(&position).unwrap_or(&self.default).clone())
+            .collect();
+
+        Some((Grid::new(width, height, entries), min))
+    }
+}
+
+impl<T> ReadGrid for SparseGrid<T> {
+    type Tile = T;
+
+    fn get(&self, position: &Position) -> Option<&T> {
+        Some(self.tiles.get(position).unwrap_or(&self.default))
+    }
+}
+
+/// Rectangular part of a [`Grid`], addressed relative to its origin.
+pub struct Window<'a, T> {
+    grid: &'a Grid<T>,
+    origin: Position,
+    width: i64,
+    height: i64,
+}
+
+impl<'a, T> Window<'a, T> {
+    pub fn origin(&self) -> Position {
+        self.origin
+    }
+
+    pub fn width(&self) -> i64 {
+        self.width
+    }
+
+    pub fn height(&self) -> i64 {
+        self.height
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        (0..self.width).contains(&position.x) && (0..self.height).contains(&position.y)
+    }
+
+    pub fn get(&self, position: &Position) -> Option<&'a T> {
+        if self.contains(position) {
+            self.grid.get(&(self.origin + position))
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> {
+        let (grid, origin, width) = (self.grid, self.origin, self.width as usize);
+        (origin.y..origin.y + self.height).map(move |y| {
+            let start = origin.x as usize;
+            &grid.row(y as usize)[start..start + width]
+        })
+    }
+
+    /// Tiles with their positions relative to the origin, in reading order.
+    pub fn iter_positions(&self) -> impl Iterator<Item = (Position, &'a T)> {
+        self.rows().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, tile)| (Position::new(x as i64, y as i64), tile))
+        })
+    }
+}
+
+impl<T> ReadGrid for Window<'_, T> {
+    type Tile = T;
+
+    fn get(&self, position: &Position) -> Option<&T> {
+        Window::get(self, position)
+    }
+}
+
+/// Which tiles around a tile count as its neighbours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Neighborhood {
+    /// The four tiles sharing an edge, in the order of [`Direction::ALL`]
+    Orthogonal,
+    /// The eight tiles sharing an edge or a corner
+    Moore,
+}
+
+impl Neighborhood {
+    const OFFSETS: [(i64, i64); 8] = [
+        (0, -1),
+        (0, 1),
+        (-1, 0),
+        (1, 0),
+        (-1, -1),
+        (1, -1),
+        (-1, 1),
+        (1, 1),
+    ];
+
+    pub fn offsets(&self) -> impl Iterator<Item = Position> {
+        let count = match self {
+            Self::Orthogonal => 4,
+            Self::Moore => 8,
+        };
+
+        Self::OFFSETS[..count]
+            .iter()
+            .map(|&(x, y)| Position::new(x, y))
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: Clone,
+{
+    /// Copy with rows and columns swapped, mirrored along the diagonal from
+    /// the top left.
+    pub fn transposed(&self) -> Self {
+        self.remapped(self.height, self.width, |position| {
+            Position::new(position.y, position.x)
+        })
+    }
+
+    /// Copy turned a quarter clockwise.
+    pub fn rotated_clockwise(&self) -> Self {
+        self.remapped(self.height, self.width, |position| {
+            Position::new(position.y, self.height - 1 - position.x)
+        })
+    }
+
+    /// Copy turned a quarter counterclockwise, or three quarters clockwise.
+    pub fn rotated_counterclockwise(&self) -> Self {
+        self.remapped(self.height, self.width, |position| {
+            Position::new(self.width - 1 - position.y, position.x)
+        })
+    }
+
+    /// Copy turned upside down.
+    pub fn rotated_half(&self) -> Self {
+        self.remapped(self.width, self.height, |position| {
+            Position::new(self.width - 1 - position.x, self.height - 1 - position.y)
+        })
+    }
+
+    /// Copy mirrored left to right.
+    pub fn flipped_horizontally(&self) -> Self {
+        self.remapped(self.width, self.height, |position| {
+            Position::new(self.width - 1 - position.x, position.y)
+        })
+    }
+
+    /// Copy mirrored top to bottom.
+    pub fn flipped_vertically(&self) -> Self {
+        self.remapped(self.width, self.height, |position| {
+            Position::new(position.x, self.height - 1 - position.y)
+        })
+    }
+
+    /// Grid of the given size, taking every tile from the position `source`
+    /// maps it to in this grid.
+    fn remapped(&self, width: i64, height: i64, source: impl Fn(Position) -> Position) -> Self {
+        let entries = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Position::new(x, y)))
+            .map(|position| {
+                self.get(&source(position))
+                    .expect("Remapped position outside the grid")
+                    .clone()
+            })
+            .collect();
+
+        Self {
+            width,
+            height,
+            entries,
+        }
+    }
 }
 
 impl<T> Grid<T>
@@ -201,6 +741,14 @@ where
     }
 }
 
+impl std::str::FromStr for Grid<char> {
+    type Err = SolveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, |c, _| Ok::<_, String>(c))
+    }
+}
+
 impl<T> std::ops::Index<usize> for Grid<T> {
     type Output = T;
 
@@ -340,3 +888,227 @@ pub enum Orientation {
     Horizontal,
     Vertical,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let (grid, starts) = Grid::parse_marked("#S.\n.#S\n", 'S', |c, _| match c {
+            '#' => Ok(true),
+            '.' | 'S' => Ok(false),
+            _ => Err("Expected `.`, `#` or `S`"),
+        })
+        .unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.iter().filter(|&&rock| rock).count(), 2);
+        assert_eq!(starts, [Position::new(1, 0), Position::new(2, 1)]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = "...\n..\n".parse::<Grid<char>>().err();
+        assert_eq!(
+            error,
+            Some(SolveError::Parse {
+                line: 2,
+                column: 1,
+                message: "Expected 3 tiles as in the first row, found 2".to_string()
+            })
+        );
+
+        let error = Grid::parse("12\n3x\n", |c, _| c.to_digit(10).ok_or("Expected a digit")).err();
+        assert_eq!(
+            error,
+            Some(SolveError::Parse {
+                line: 2,
+                column: 2,
+                message: "Expected a digit".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = "abc\ndef\nghi\n".parse::<Grid<char>>().unwrap();
+        let corner = Position::new(0, 0);
+        let tiles = |neighbors: &mut dyn Iterator<Item = (Position, &char)>| {
+            neighbors.map(|(_, &tile)| tile).collect::<String>()
+        };
+
+        assert_eq!(
+            tiles(&mut grid.neighbors(&corner, Neighborhood::Orthogonal)),
+            "db"
+        );
+        assert_eq!(
+            tiles(&mut grid.neighbors(&corner, Neighborhood::Moore)),
+            "dbe"
+        );
+        assert_eq!(
+            tiles(&mut grid.wrapping_neighbors(&corner, Neighborhood::Orthogonal)),
+            "gdcb"
+        );
+        assert_eq!(
+            tiles(&mut grid.wrapping_neighbors(&corner, Neighborhood::Moore)),
+            "gdcbihfe"
+        );
+
+        let directions = grid
+            .adjacent(&corner, [Direction::Up, Direction::Right])
+            .map(|(direction, position, _)| (direction, position))
+            .collect::<Vec<_>>();
+        assert_eq!(directions, [(Direction::Right, Position::new(1, 0))]);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let mut grid = "abc\ndef\n".parse::<Grid<char>>().unwrap();
+        *grid.get_mut(&Position::new(1, 1)).unwrap() = 'E';
+
+        let rows = grid.rows().map(String::from_iter).collect::<Vec<_>>();
+        assert_eq!(rows, ["abc", "dEf"]);
+
+        let columns = grid.columns().map(String::from_iter).collect::<Vec<_>>();
+        assert_eq!(columns, ["ad", "bE", "cf"]);
+
+        let (position, tile) = grid.iter_positions().nth(4).unwrap();
+        assert_eq!((position, *tile), (Position::new(1, 1), 'E'));
+    }
+
+    #[test]
+    fn test_windows() {
+        let grid = "abcd\nefgh\nijkl\n".parse::<Grid<char>>().unwrap();
+
+        let window = grid.window(&Position::new(1, 1), 2, 2).unwrap();
+        assert_eq!(window.get(&Position::new(1, 0)), Some(&'g'));
+        assert_eq!(window.get(&Position::new(2, 0)), None);
+        assert_eq!(
+            window
+                .iter_positions()
+                .map(|(_, tile)| tile)
+                .collect::<String>(),
+            "fgjk"
+        );
+        assert!(grid.window(&Position::new(3, 0), 2, 1).is_none());
+
+        let origins = grid
+            .windows(3, 2)
+            .map(|window| window.origin())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            origins,
+            [
+                Position::new(0, 0),
+                Position::new(1, 0),
+                Position::new(0, 1),
+                Position::new(1, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = "ab\ncd\nef\n".parse::<Grid<char>>().unwrap();
+        let rows = |grid: Grid<char>| grid.rows().map(String::from_iter).collect::<Vec<_>>();
+
+        assert_eq!(rows(grid.transposed()), ["ace", "bdf"]);
+        assert_eq!(rows(grid.rotated_clockwise()), ["eca", "fdb"]);
+        assert_eq!(rows(grid.rotated_counterclockwise()), ["bdf", "ace"]);
+        assert_eq!(rows(grid.rotated_half()), ["fe", "dc", "ba"]);
+        assert_eq!(rows(grid.flipped_horizontally()), ["ba", "dc", "fe"]);
+        assert_eq!(rows(grid.flipped_vertically()), ["ef", "cd", "ab"]);
+        assert_eq!(
+            rows(grid.rotated_clockwise().rotated_clockwise()),
+            rows(grid.rotated_half())
+        );
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut grid = "O.#.O\n.O..O\n".parse::<Grid<char>>().unwrap();
+        let rows = |grid: &Grid<char>| grid.rows().map(String::from_iter).collect::<Vec<_>>();
+        let (moves, blocks) = (|c: &char| *c == 'O', |c: &char| *c == '#');
+
+        grid.compact(Direction::Right, moves, blocks);
+        assert_eq!(rows(&grid), [".O#.O", "...OO"]);
+
+        grid.compact(Direction::Up, moves, blocks);
+        assert_eq!(rows(&grid), [".O#OO", "....O"]);
+
+        grid.compact(Direction::Left, moves, blocks);
+        assert_eq!(rows(&grid), ["O.#OO", "O...."]);
+
+        grid.compact(Direction::Down, moves, blocks);
+        assert_eq!(rows(&grid), ["O.#..", "O..OO"]);
+    }
+
+    #[test]
+    fn test_tiled() {
+        let tiled = TiledGrid::new("ab\ncd\n".parse::<Grid<char>>().unwrap());
+
+        assert_eq!(tiled.get(&Position::new(-1, -1)), Some(&'d'));
+        assert_eq!(tiled.get(&Position::new(4, 3)), Some(&'c'));
+        assert_eq!(tiled.copy_of(&Position::new(-1, 2)), Position::new(-1, 1));
+
+        let neighbors = tiled
+            .neighbors(&Position::new(0, 0), Neighborhood::Orthogonal)
+            .map(|(position, &tile)| (position, tile))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            neighbors,
+            [
+                (Position::new(0, -1), 'c'),
+                (Position::new(0, 1), 'c'),
+                (Position::new(-1, 0), 'b'),
+                (Position::new(1, 0), 'b')
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sparse() {
+        let mut sparse = SparseGrid::new('.');
+        sparse.put(&Position::new(-2, 1), '#');
+        *sparse.get_mut(&Position::new(1, -1)) = 'O';
+
+        assert_eq!(sparse.len(), 2);
+        assert_eq!(sparse.get(&Position::new(100, 100)), Some(&'.'));
+        assert_eq!(
+            sparse.bounds(),
+            Some((Position::new(-2, -1), Position::new(1, 1)))
+        );
+
+        let (grid, origin) = sparse.to_grid().unwrap();
+        assert_eq!(origin, Position::new(-2, -1));
+        assert_eq!(
+            grid.rows().map(String::from_iter).collect::<Vec<_>>(),
+            ["...O", "....", "#..."]
+        );
+
+        let neighbors = sparse
+            .neighbors(&Position::new(1, 0), Neighborhood::Orthogonal)
+            .map(|(position, &tile)| (position, tile))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            neighbors,
+            [
+                (Position::new(1, -1), 'O'),
+                (Position::new(1, 1), '.'),
+                (Position::new(0, 0), '.'),
+                (Position::new(2, 0), '.')
+            ]
+        );
+
+        assert_eq!(sparse.remove(&Position::new(-2, 1)), Some('#'));
+        assert_eq!(sparse.get(&Position::new(-2, 1)), Some(&'.'));
+    }
+
+    #[test]
+    fn test_from_str() {
+        let grid = "ab\ncd\n".parse::<Grid<char>>().unwrap();
+        assert_eq!(grid.get(&Position::new(1, 1)), Some(&'d'));
+        assert_eq!(grid.to_char_grid().iter().collect::<String>(), "abcd");
+    }
+}
@@ -0,0 +1,481 @@
+use nalgebra::{vector, Vector3};
+
+use crate::grid::{self, Grid};
+
+pub type Position = Vector3<i64>;
+
+impl std::ops::AddAssign<Direction3> for Position {
+    fn add_assign(&mut self, rhs: Direction3) {
+        *self += rhs.offset();
+    }
+}
+
+impl std::ops::Add<Direction3> for Position {
+    type Output = Self;
+
+    fn add(mut self, rhs: Direction3) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl std::ops::SubAssign<Direction3> for Position {
+    fn sub_assign(&mut self, rhs: Direction3) {
+        *self -= rhs.offset();
+    }
+}
+
+impl std::ops::Sub<Direction3> for Position {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Direction3) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+/// Dense box of tiles, `width` along x, `depth` along y and `height` along z,
+/// with a corner at the origin.
+#[derive(Clone)]
+pub struct Grid3<T> {
+    width: i64,
+    depth: i64,
+    height: i64,
+    entries: Vec<T>,
+}
+
+impl<T> Grid3<T> {
+    pub fn new(width: usize, depth: usize, height: usize, entries: Vec<T>) -> Self {
+        assert_eq!(width * depth * height, entries.len());
+
+        Self {
+            width: width as i64,
+            depth: depth as i64,
+            height: height as i64,
+            entries,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn width(&self) -> i64 {
+        self.width
+    }
+
+    pub fn depth(&self) -> i64 {
+        self.depth
+    }
+
+    pub fn height(&self) -> i64 {
+        self.height
+    }
+
+    /// The cuboid covering every tile, or `None` for an empty grid.
+    pub fn bounds(&self) -> Option<Cuboid> {
+        (!self.is_empty()).then(|| {
+            Cuboid::new(
+                Position::zeros(),
+                vector![self.width - 1, self.depth - 1, self.height - 1],
+            )
+        })
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        (0..self.width).contains(&position.x)
+            && (0..self.depth).contains(&position.y)
+            && (0..self.height).contains(&position.z)
+    }
+
+    pub fn index(&self, position: &Position) -> Option<usize> {
+        if self.contains(position) {
+            Some(((position.z * self.depth + position.y) * self.width + position.x) as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn position(&self, index: usize) -> Position {
+        let index = index as i64;
+        vector![
+            index % self.width,
+            index / self.width % self.depth,
+            index / (self.width * self.depth)
+        ]
+    }
+
+    pub fn get(&self, position: &Position) -> Option<&T> {
+        self.index(position).and_then(|idx| self.entries.get(idx))
+    }
+
+    pub fn get_mut(&mut self, position: &Position) -> Option<&mut T> {
+        self.index(position)
+            .and_then(|idx| self.entries.get_mut(idx))
+    }
+
+    pub fn put(&mut self, position: &Position, tile: T) {
+        if let Some(index) = self.index(position) {
+            self.entries[index] = tile;
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.entries.iter()
+    }
+
+    /// Tiles with their positions, by layer from the bottom, then by row.
+    pub fn iter_positions(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(idx, tile)| (self.position(idx), tile))
+    }
+
+    /// Tiles one step from `position` in each of the six directions that lie
+    /// within the grid.
+    pub fn adjacent(
+        &self,
+        position: &Position,
+    ) -> impl Iterator<Item = (Direction3, Position, &T)> {
+        let position = *position;
+        Direction3::ALL.into_iter().filter_map(move |direction| {
+            let neighbor = position + direction;
+            self.get(&neighbor).map(|tile| (direction, neighbor, tile))
+        })
+    }
+
+    /// Projects the grid onto the floor, giving the level of the highest
+    /// tile of every column that is `filled`.
+    pub fn height_map(&self, filled: impl Fn(&T) -> bool) -> HeightMap {
+        let mut heights = HeightMap::new(self.width as usize, self.depth as usize);
+        for (position, _) in self.iter_positions().filter(|(_, tile)| filled(tile)) {
+            heights.raise(&Cuboid::new(position, position));
+        }
+
+        heights
+    }
+}
+
+impl<T> Grid3<T>
+where
+    T: Clone,
+{
+    pub fn filled(width: usize, depth: usize, height: usize, tile: T) -> Self {
+        Self::new(width, depth, height, vec![tile; width * depth * height])
+    }
+}
+
+impl<T> std::fmt::Debug for Grid3<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Grid3")
+            .field("width", &self.width)
+            .field("depth", &self.depth)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Coordinate axis, indexing the components of a [`Position`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    pub const ALL: [Self; 3] = [Self::X, Self::Y, Self::Z];
+
+    pub const fn index(&self) -> usize {
+        match self {
+            Self::X => 0,
+            Self::Y => 1,
+            Self::Z => 2,
+        }
+    }
+}
+
+/// The six directions along the axes. Up and down follow z, north and south
+/// follow y with north towards smaller y, and east and west follow x with
+/// east towards larger x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction3 {
+    Up,
+    Down,
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Direction3 {
+    pub const ALL: [Self; 6] = [
+        Self::Up,
+        Self::Down,
+        Self::North,
+        Self::South,
+        Self::East,
+        Self::West,
+    ];
+
+    pub const fn inverse(&self) -> Self {
+        match self {
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::North => Self::South,
+            Self::South => Self::North,
+            Self::East => Self::West,
+            Self::West => Self::East,
+        }
+    }
+
+    pub const fn axis(&self) -> Axis {
+        match self {
+            Self::Up | Self::Down => Axis::Z,
+            Self::North | Self::South => Axis::Y,
+            Self::East | Self::West => Axis::X,
+        }
+    }
+
+    /// Whether a step in this direction increases the coordinate on its axis.
+    pub const fn is_positive(&self) -> bool {
+        matches!(self, Self::Up | Self::South | Self::East)
+    }
+
+    /// The position change of one step in this direction.
+    pub fn offset(&self) -> Position {
+        let mut offset = Position::zeros();
+        offset[self.axis().index()] = if self.is_positive() { 1 } else { -1 };
+        offset
+    }
+}
+
+/// Axis-aligned box of voxels between two corners, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub min: Position,
+    pub max: Position,
+}
+
+impl Cuboid {
+    /// The cuboid spanned by two opposite corners, in any order.
+    pub fn new(a: Position, b: Position) -> Self {
+        Self {
+            min: a.inf(&b),
+            max: a.sup(&b),
+        }
+    }
+
+    /// Number of voxels along each axis.
+    pub fn size(&self) -> Position {
+        self.max - self.min + vector![1, 1, 1]
+    }
+
+    pub fn volume(&self) -> u64 {
+        self.size().iter().map(|&length| length as u64).product()
+    }
+
+    pub fn contains(&self, position: &Position) -> bool {
+        (0..3).all(|axis| (self.min[axis]..=self.max[axis]).contains(&position[axis]))
+    }
+
+    /// Voxels of the cuboid, by layer from the bottom, then by row.
+    pub fn voxels(&self) -> impl Iterator<Item = Position> {
+        let (min, max) = (self.min, self.max);
+        (min.z..=max.z).flat_map(move |z| {
+            (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| vector![x, y, z]))
+        })
+    }
+
+    /// Columns the cuboid covers when seen from above.
+    pub fn footprint(&self) -> impl Iterator<Item = grid::Position> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| grid::Position::new(x, y)))
+    }
+
+    /// The layer of voxels on the side of the cuboid facing `direction`.
+    pub fn face(&self, direction: Direction3) -> Self {
+        let axis = direction.axis().index();
+        let mut face = *self;
+        if direction.is_positive() {
+            face.min[axis] = face.max[axis];
+        } else {
+            face.max[axis] = face.min[axis];
+        }
+
+        face
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let (min, max) = (self.min.sup(&other.min), self.max.inf(&other.max));
+        (0..3)
+            .all(|axis| min[axis] <= max[axis])
+            .then_some(Self { min, max })
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn translated(&self, offset: Position) -> Self {
+        Self {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+
+    /// The cuboid moved `distance` voxels towards smaller coordinates along
+    /// `axis`.
+    pub fn dropped(&self, axis: Axis, distance: i64) -> Self {
+        let mut offset = Position::zeros();
+        offset[axis.index()] = -distance;
+        self.translated(offset)
+    }
+}
+
+/// Level of the top of the highest voxel in every column of a floor, for
+/// stacking cuboids that fall along z. Empty columns have level 0, so the
+/// lowest layer a cuboid can rest on is 1.
+#[derive(Clone)]
+pub struct HeightMap {
+    levels: Grid<i64>,
+}
+
+impl HeightMap {
+    pub fn new(width: usize, depth: usize) -> Self {
+        Self {
+            levels: Grid::new(width, depth, vec![0; width * depth]),
+        }
+    }
+
+    /// Level of the highest voxel in `column`, 0 if it is empty or outside
+    /// the floor.
+    pub fn level(&self, column: &grid::Position) -> i64 {
+        self.levels.get(column).copied().unwrap_or(0)
+    }
+
+    /// Highest level under the footprint of `cuboid`.
+    pub fn top(&self, cuboid: &Cuboid) -> i64 {
+        cuboid
+            .footprint()
+            .map(|column| self.level(&column))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Raises the columns under `cuboid` to its top.
+    pub fn raise(&mut self, cuboid: &Cuboid) {
+        for column in cuboid.footprint() {
+            if let Some(level) = self.levels.get_mut(&column) {
+                *level = (*level).max(cuboid.max.z);
+            }
+        }
+    }
+
+    /// Drops `cuboid` until it rests on the columns below it and raises
+    /// them, returning where it came to rest. Cuboids already below the top
+    /// of any of their columns do not move.
+    pub fn land(&mut self, cuboid: &Cuboid) -> Cuboid {
+        let distance = (cuboid.min.z - self.top(cuboid) - 1).max(0);
+        let landed = cuboid.dropped(Axis::Z, distance);
+        self.raise(&landed);
+        landed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid3() {
+        let mut grid = Grid3::filled(2, 3, 4, 0);
+        let position = vector![1, 2, 3];
+
+        assert_eq!(grid.index(&position), Some(23));
+        assert_eq!(grid.position(23), position);
+        assert_eq!(grid.get(&vector![2, 0, 0]), None);
+        assert_eq!(
+            grid.bounds(),
+            Some(Cuboid::new(Position::zeros(), position))
+        );
+
+        grid.put(&vector![1, 1, 1], 1);
+        let neighbors = grid
+            .adjacent(&vector![1, 1, 0])
+            .map(|(direction, _, &tile)| (direction, tile))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            neighbors,
+            [
+                (Direction3::Up, 1),
+                (Direction3::North, 0),
+                (Direction3::South, 0),
+                (Direction3::West, 0)
+            ]
+        );
+
+        let heights = grid.height_map(|&tile| tile == 1);
+        assert_eq!(heights.level(&grid::Position::new(1, 1)), 1);
+        assert_eq!(heights.level(&grid::Position::new(0, 1)), 0);
+    }
+
+    #[test]
+    fn test_directions() {
+        for direction in Direction3::ALL {
+            let position = vector![3, -2, 5];
+            assert_eq!(position + direction + direction.inverse(), position);
+            assert_eq!(position - direction, position + direction.inverse());
+        }
+
+        assert_eq!(Position::zeros() + Direction3::North, vector![0, -1, 0]);
+        assert_eq!(Position::zeros() + Direction3::Up, vector![0, 0, 1]);
+    }
+
+    #[test]
+    fn test_cuboid() {
+        let cuboid = Cuboid::new(vector![2, 0, 1], vector![0, 1, 1]);
+        assert_eq!(cuboid.size(), vector![3, 2, 1]);
+        assert_eq!(cuboid.volume(), 6);
+        assert_eq!(cuboid.voxels().count(), 6);
+        assert!(cuboid.voxels().all(|voxel| cuboid.contains(&voxel)));
+        assert_eq!(cuboid.footprint().count(), 6);
+        assert_eq!(
+            cuboid.face(Direction3::East),
+            Cuboid::new(vector![2, 0, 1], vector![2, 1, 1])
+        );
+
+        let other = Cuboid::new(vector![1, 1, 0], vector![5, 5, 5]);
+        assert_eq!(
+            cuboid.intersection(&other),
+            Some(Cuboid::new(vector![1, 1, 1], vector![2, 1, 1]))
+        );
+        assert!(!cuboid.intersects(&other.dropped(Axis::X, -2)));
+        assert_eq!(cuboid.dropped(Axis::Z, 1).min.z, 0);
+    }
+
+    #[test]
+    fn test_height_map() {
+        let mut heights = HeightMap::new(3, 3);
+
+        let floor = heights.land(&Cuboid::new(vector![0, 0, 5], vector![2, 0, 5]));
+        assert_eq!(floor, Cuboid::new(vector![0, 0, 1], vector![2, 0, 1]));
+
+        let tower = heights.land(&Cuboid::new(vector![1, 0, 9], vector![1, 0, 11]));
+        assert_eq!(tower, Cuboid::new(vector![1, 0, 2], vector![1, 0, 4]));
+
+        let beam = heights.land(&Cuboid::new(vector![0, 0, 7], vector![0, 2, 7]));
+        assert_eq!(beam.min.z, 2);
+        assert_eq!(heights.level(&grid::Position::new(1, 0)), 4);
+        assert_eq!(heights.level(&grid::Position::new(0, 2)), 2);
+        assert_eq!(heights.level(&grid::Position::new(2, 2)), 0);
+    }
+}
@@ -1,10 +1,16 @@
+pub mod answer;
 pub mod cache;
+pub mod error;
 pub mod geometry;
 pub mod graph;
 pub mod grid;
+pub mod grid3;
 pub mod numerics;
+pub mod random;
 pub mod range;
 pub mod sequence;
 pub mod slice;
+pub mod solution;
 pub mod test;
 pub mod tree;
+pub mod validation;
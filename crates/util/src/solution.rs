@@ -0,0 +1,218 @@
+use std::{any::Any, sync::Arc};
+
+use crate::{answer::Answer, error::SolveError, random::Rng, validation::Check};
+
+/// A puzzle solution. Every day crate implements this on a unit struct named
+/// `Day`, which the runner picks up from its manifest at build time.
+pub trait Solution {
+    const ID: u32;
+    const YEAR: u32 = 2023;
+    const TITLE: &'static str;
+
+    type Answer1: Into<Answer> + 'static;
+    type Answer2: Into<Answer> + 'static;
+
+    fn part1(input: &str) -> Result<Self::Answer1, SolveError>;
+    fn part2(input: &str) -> Result<Self::Answer2, SolveError>;
+
+    fn part1_examples() -> Vec<Example<Self::Answer1>> {
+        Vec::new()
+    }
+
+    fn part2_examples() -> Vec<Example<Self::Answer2>> {
+        Vec::new()
+    }
+
+    /// Parse and solve steps of part 1, for days that keep them apart so the
+    /// runner can time them separately. Days returning `None` are timed as a
+    /// whole through [`Solution::part1`].
+    fn part1_phases() -> Option<Phases<Self::Answer1>> {
+        None
+    }
+
+    fn part2_phases() -> Option<Phases<Self::Answer2>> {
+        None
+    }
+
+    /// Checks the structural properties of the input that the parts rely on
+    /// beyond what the puzzle description guarantees, so unsupported inputs
+    /// are known before solving. Fails only if the input cannot be parsed.
+    fn validate(_input: &str) -> Result<Vec<Check>, SolveError> {
+        Ok(Vec::new())
+    }
+
+    /// Generates a random valid input that grows with `size`, for stress
+    /// testing and benchmarking how the parts scale. Days returning `None`
+    /// have no generator.
+    fn generate(_size: usize, _rng: &mut Rng) -> Option<String> {
+        None
+    }
+}
+
+/// A part split into a parse step and a solve step working on its output.
+#[allow(clippy::type_complexity)]
+pub struct Phases<T> {
+    parse: Box<dyn Fn(&str) -> Result<Box<dyn Any>, SolveError> + Send + Sync>,
+    solve: Box<dyn Fn(&dyn Any) -> Result<T, SolveError> + Send + Sync>,
+}
+
+impl<T> Phases<T> {
+    pub fn new<P>(
+        parse: fn(&str) -> Result<P, SolveError>,
+        solve: fn(&P) -> Result<T, SolveError>,
+    ) -> Self
+    where
+        P: 'static,
+        T: 'static,
+    {
+        Self {
+            parse: Box::new(move |input| Ok(Box::new(parse(input)?))),
+            solve: Box::new(move |parsed| {
+                solve(
+                    parsed
+                        .downcast_ref()
+                        .expect("Parsed input of another solution"),
+                )
+            }),
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Any>, SolveError> {
+        (self.parse)(input)
+    }
+
+    pub fn solve(&self, parsed: &dyn Any) -> Result<T, SolveError> {
+        (self.solve)(parsed)
+    }
+}
+
+impl<T> Phases<T>
+where
+    T: Into<Answer> + 'static,
+{
+    fn erase(self) -> Phases<Answer> {
+        let solve = self.solve;
+
+        Phases {
+            parse: self.parse,
+            solve: Box::new(move |parsed| solve(parsed).map(Into::into)),
+        }
+    }
+}
+
+/// A worked example from the puzzle description, with its expected answer.
+pub struct Example<T> {
+    pub input: &'static str,
+    /// Parameters that differ from the real puzzle, such as a step count.
+    pub parameters: &'static [(&'static str, i64)],
+    pub expected: T,
+    pub solve: fn(&str) -> Result<T, SolveError>,
+}
+
+impl<T> Example<T> {
+    pub fn new(input: &'static str, expected: T, solve: fn(&str) -> Result<T, SolveError>) -> Self {
+        Self {
+            input,
+            parameters: &[],
+            expected,
+            solve,
+        }
+    }
+
+    pub fn with_parameters(self, parameters: &'static [(&'static str, i64)]) -> Self {
+        Self { parameters, ..self }
+    }
+}
+
+/// Type-erased [`Example`], as exposed through [`DynSolution`].
+#[allow(clippy::type_complexity)]
+pub struct DynExample {
+    pub part: u32,
+    pub input: &'static str,
+    pub parameters: &'static [(&'static str, i64)],
+    pub expected: Answer,
+    pub solve: Arc<dyn Fn(&str) -> Result<Answer, SolveError> + Send + Sync>,
+}
+
+impl DynExample {
+    fn new<T>(part: u32, example: Example<T>) -> Self
+    where
+        T: Into<Answer> + 'static,
+    {
+        let solve = example.solve;
+
+        Self {
+            part,
+            input: example.input,
+            parameters: example.parameters,
+            expected: example.expected.into(),
+            solve: Arc::new(move |input| solve(input).map(Into::into)),
+        }
+    }
+}
+
+/// Object-safe view of a [`Solution`], so solutions can be stored together in
+/// a registry.
+pub trait DynSolution: Sync {
+    fn id(&self) -> u32;
+    fn year(&self) -> u32;
+    fn title(&self) -> &'static str;
+    fn part1(&self, input: &str) -> Result<Answer, SolveError>;
+    fn part2(&self, input: &str) -> Result<Answer, SolveError>;
+    fn examples(&self) -> Vec<DynExample>;
+    fn phases(&self, part: u32) -> Option<Phases<Answer>>;
+    fn validate(&self, input: &str) -> Result<Vec<Check>, SolveError>;
+    fn generate(&self, size: usize, rng: &mut Rng) -> Option<String>;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+{
+    fn id(&self) -> u32 {
+        S::ID
+    }
+
+    fn year(&self) -> u32 {
+        S::YEAR
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn part1(&self, input: &str) -> Result<Answer, SolveError> {
+        S::part1(input).map(Into::into)
+    }
+
+    fn part2(&self, input: &str) -> Result<Answer, SolveError> {
+        S::part2(input).map(Into::into)
+    }
+
+    fn examples(&self) -> Vec<DynExample> {
+        let part1 = S::part1_examples()
+            .into_iter()
+            .map(|example| DynExample::new(1, example));
+        let part2 = S::part2_examples()
+            .into_iter()
+            .map(|example| DynExample::new(2, example));
+
+        part1.chain(part2).collect()
+    }
+
+    fn phases(&self, part: u32) -> Option<Phases<Answer>> {
+        match part {
+            1 => S::part1_phases().map(Phases::erase),
+            2 => S::part2_phases().map(Phases::erase),
+            _ => None,
+        }
+    }
+
+    fn validate(&self, input: &str) -> Result<Vec<Check>, SolveError> {
+        S::validate(input)
+    }
+
+    fn generate(&self, size: usize, rng: &mut Rng) -> Option<String> {
+        S::generate(size, rng)
+    }
+}
@@ -0,0 +1,43 @@
+use crate::error::SolveError;
+
+/// A structural property of the input that a part relies on, and whether the
+/// input has it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub part: u32,
+    pub assumption: String,
+    /// Why the input breaks the assumption, if it does
+    pub violation: Option<String>,
+}
+
+impl Check {
+    pub fn new(part: u32, assumption: impl Into<String>, result: Result<(), String>) -> Self {
+        Self {
+            part,
+            assumption: assumption.into(),
+            violation: result.err(),
+        }
+    }
+
+    pub fn holds(&self) -> bool {
+        self.violation.is_none()
+    }
+}
+
+impl std::fmt::Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.violation {
+            None => write!(f, "{}", self.assumption),
+            Some(violation) => write!(f, "{}: {violation}", self.assumption),
+        }
+    }
+}
+
+/// The first check that fails, as the error a part returns when it cannot
+/// solve an unsupported input.
+pub fn first_violation<'a>(checks: impl IntoIterator<Item = &'a Check>) -> Result<(), SolveError> {
+    match checks.into_iter().find(|check| !check.holds()) {
+        Some(check) => Err(SolveError::unsolvable(check.to_string())),
+        None => Ok(()),
+    }
+}
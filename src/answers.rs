@@ -0,0 +1,153 @@
+use std::{collections::HashMap, path::Path};
+
+use aoc_util::answer::Answer;
+
+use crate::AocError;
+
+/// Name of the known answers file in the inputs directory, used unless
+/// another file is given.
+pub const FILE_NAME: &str = "answers.toml";
+
+/// Known answers, read from a TOML file with one table per day:
+///
+/// ```toml
+/// [day-01]
+/// part1 = 142
+/// part2 = "281"
+/// ```
+#[derive(Default)]
+pub struct Answers {
+    entries: HashMap<(u32, u32), Answer>,
+}
+
+impl Answers {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AocError> {
+        let contents = std::fs::read_to_string(path)?;
+        contents.parse()
+    }
+
+    pub fn get(&self, puzzle: u32, part: u32) -> Option<&Answer> {
+        self.entries.get(&(puzzle, part))
+    }
+
+    pub fn verify(&self, puzzle: u32, part: u32, actual: &Answer) -> Verdict {
+        Verdict::new(self.get(puzzle, part), actual)
+    }
+}
+
+impl std::str::FromStr for Answers {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table: toml::Table = s.parse()?;
+        let mut entries = HashMap::new();
+
+        for (day, parts) in table.iter() {
+            let puzzle = day
+                .strip_prefix("day-")
+                .and_then(|id| id.parse().ok())
+                .ok_or_else(|| AocError::InvalidAnswers(format!("Unexpected table {day:?}")))?;
+
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| AocError::InvalidAnswers(format!("{day:?} is not a table")))?;
+
+            for (part, answer) in parts.iter() {
+                let part = match part.as_str() {
+                    "part1" => 1,
+                    "part2" => 2,
+                    _ => {
+                        return Err(AocError::InvalidAnswers(format!(
+                            "Unexpected key {part:?} in {day:?}"
+                        )))
+                    }
+                };
+
+                let answer = match answer {
+                    toml::Value::String(answer) => {
+                        let Ok(answer) = answer.parse();
+                        answer
+                    }
+                    toml::Value::Integer(answer) => Answer::Integer(*answer),
+                    _ => {
+                        return Err(AocError::InvalidAnswers(format!(
+                            "Answer for {day:?} part {part} must be a string or an integer"
+                        )))
+                    }
+                };
+
+                entries.insert((puzzle, part), answer);
+            }
+        }
+
+        Ok(Self { entries })
+    }
+}
+
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer },
+    Unknown,
+}
+
+impl Verdict {
+    pub fn new(expected: Option<&Answer>, actual: &Answer) -> Self {
+        match expected {
+            Some(expected) if expected == actual => Self::Pass,
+            Some(expected) => Self::Fail {
+                expected: expected.clone(),
+            },
+            None => Self::Unknown,
+        }
+    }
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pass => write!(f, "PASS"),
+            Self::Fail { expected } => write!(f, "FAIL, expected {expected}"),
+            Self::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_ANSWERS: &str = r#"
+[day-01]
+part1 = 142
+part2 = "281"
+
+[day-25]
+part1 = 54
+"#;
+
+    #[test]
+    fn test_parse() {
+        let answers: Answers = TEST_ANSWERS.parse().unwrap();
+        assert_eq!(answers.get(1, 1), Some(&Answer::Integer(142)));
+        assert_eq!(answers.get(1, 2), Some(&Answer::Integer(281)));
+        assert_eq!(answers.get(25, 1), Some(&Answer::Integer(54)));
+        assert_eq!(answers.get(25, 2), None);
+    }
+
+    #[test]
+    fn test_verify() {
+        let answers: Answers = TEST_ANSWERS.parse().unwrap();
+        assert!(matches!(
+            answers.verify(1, 1, &142u32.into()),
+            Verdict::Pass
+        ));
+        assert!(matches!(
+            answers.verify(1, 2, &280usize.into()),
+            Verdict::Fail { expected } if expected == Answer::Integer(281)
+        ));
+        assert!(matches!(
+            answers.verify(2, 1, &8u64.into()),
+            Verdict::Unknown
+        ));
+    }
+}
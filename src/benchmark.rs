@@ -1,39 +1,264 @@
-use std::time::{Duration, Instant};
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicU8, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
-#[allow(clippy::type_complexity)]
-pub fn measure<R>(
-    function: impl Fn(&str) -> R + 'static,
-    rounds: u32,
-) -> Box<dyn Fn(&str) -> (RuntimeStats, String)>
+use aoc_util::{answer::Answer, error::SolveError, solution::Phases};
+
+use crate::memory::{self, MemoryStats};
+
+/// How many times, or for how long, each part is measured.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchmarkConfig {
+    pub rounds: u32,
+    pub warmup: u32,
+    pub budget: Option<Duration>,
+}
+
+impl BenchmarkConfig {
+    pub fn is_single(&self) -> bool {
+        self.budget.is_none() && self.rounds <= 1
+    }
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            rounds: 1,
+            warmup: 0,
+            budget: None,
+        }
+    }
+}
+
+/// Number of timed out parts still finishing a round in the background. They
+/// compete for the CPU, so while there are any, parts are measured once
+/// instead of being benchmarked.
+static IN_BACKGROUND: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The part measured on this thread, when it runs under a timeout
+    static CURRENT: RefCell<Option<Abandonment>> = const { RefCell::new(None) };
+}
+
+const RUNNING: u8 = 0;
+const FINISHED: u8 = 1;
+const ABANDONED: u8 = 2;
+
+/// Lets the thread waiting for a part give up on it. An abandoned part stops
+/// before its next round, but the round in progress runs to completion.
+#[derive(Clone, Default)]
+pub struct Abandonment(Arc<AtomicU8>);
+
+impl Abandonment {
+    /// Measures a part on the current thread, which must be its own.
+    pub fn run<R>(&self, part: impl FnOnce() -> R) -> R {
+        CURRENT.with(|current| *current.borrow_mut() = Some(self.clone()));
+        let result = part();
+
+        if self.0.swap(FINISHED, Ordering::SeqCst) == ABANDONED {
+            IN_BACKGROUND.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        result
+    }
+
+    /// Gives up on the part, unless it has just finished.
+    pub fn abandon(&self) {
+        if self
+            .0
+            .compare_exchange(RUNNING, ABANDONED, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+        {
+            IN_BACKGROUND.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Whether the part measured on this thread was given up on.
+fn abandoned() -> bool {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .is_some_and(|part| part.0.load(Ordering::SeqCst) == ABANDONED)
+    })
+}
+
+/// Whether timed out parts are still running, so that benchmarks would be
+/// skewed.
+pub fn contended() -> bool {
+    IN_BACKGROUND.load(Ordering::SeqCst) > 0
+}
+
+/// Measured part, which stops at the first round that fails.
+pub type Measured<'a> =
+    Box<dyn Fn(&str) -> Result<(Timings, Answer), SolveError> + Send + Sync + 'a>;
+
+/// Called with the number of measured rounds so far and the total duration of
+/// the latest one, after every measured round.
+pub type Progress = Arc<dyn Fn(usize, Duration) + Send + Sync>;
+
+pub fn measure<'a, R>(
+    function: impl Fn(&str) -> Result<R, SolveError> + Send + Sync + 'a,
+    config: BenchmarkConfig,
+    progress: Option<Progress>,
+) -> Measured<'a>
 where
-    R: std::fmt::Display,
+    R: Into<Answer>,
 {
-    if rounds <= 1 {
-        Box::new(move |input| {
+    Box::new(move |input| {
+        let (result, [total], memory) = sample(config, progress.as_ref(), || {
             let start = Instant::now();
-            let result = function(input);
-            let duration = start.elapsed();
-            (duration.into(), result.to_string())
-        })
-    } else {
-        Box::new(move |input| {
-            let mut accumulator = Vec::with_capacity(rounds as usize);
+            let result = function(input)?;
+            Ok((result, [start.elapsed()]))
+        })?;
+
+        let timings = Timings {
+            total,
+            phases: None,
+            memory,
+        };
 
-            let mut result = None;
+        Ok((timings, result.into()))
+    })
+}
 
-            for _ in 0..rounds {
-                let start = Instant::now();
-                let round_result = function(input);
-                let duration = start.elapsed();
-                accumulator.push(duration);
+/// Like [`measure`], but times the parse and solve steps of a part separately.
+pub fn measure_phases<'a, R>(
+    phases: Phases<R>,
+    config: BenchmarkConfig,
+    progress: Option<Progress>,
+) -> Measured<'a>
+where
+    R: Into<Answer> + 'a,
+{
+    Box::new(move |input| {
+        let (result, [parse, solve, total], memory) = sample(config, progress.as_ref(), || {
+            let start = Instant::now();
+            let parsed = phases.parse(input)?;
+            let parse = start.elapsed();
 
-                if result.is_none() {
-                    result = Some(round_result);
-                }
-            }
+            let start = Instant::now();
+            let result = phases.solve(parsed.as_ref())?;
+            let solve = start.elapsed();
+
+            Ok((result, [parse, solve, parse + solve]))
+        })?;
+
+        let timings = Timings {
+            total,
+            phases: Some((parse, solve)),
+            memory,
+        };
+
+        Ok((timings, result.into()))
+    })
+}
 
-            (accumulator.into(), result.unwrap().to_string())
-        })
+/// Runs `round` as configured, collecting the durations it reports and the
+/// heap usage of the first measured round. The last duration of a round is
+/// its total, which is what `progress` is told about.
+#[allow(clippy::type_complexity)]
+fn sample<R, const N: usize>(
+    config: BenchmarkConfig,
+    progress: Option<&Progress>,
+    round: impl Fn() -> Result<(R, [Duration; N]), SolveError>,
+) -> Result<(R, [RuntimeStats; N], Option<MemoryStats>), SolveError> {
+    let stop = || {
+        if abandoned() {
+            Err(SolveError::unsolvable("Abandoned after timing out"))
+        } else {
+            Ok(())
+        }
+    };
+
+    for _ in 0..config.warmup {
+        stop()?;
+        round()?;
+    }
+
+    let mut accumulator: [Vec<Duration>; N] =
+        std::array::from_fn(|_| Vec::with_capacity(config.rounds as usize));
+
+    let mut result = None;
+    let mut memory = None;
+
+    let start = Instant::now();
+    loop {
+        stop()?;
+
+        let done = match config.budget {
+            Some(budget) => result.is_some() && start.elapsed() >= budget,
+            None => result.is_some() && accumulator[0].len() >= config.rounds as usize,
+        };
+
+        if done || (result.is_some() && contended()) {
+            break;
+        }
+
+        let (round_result, durations) = if result.is_none() {
+            let (round_result, stats) = memory::track(&round);
+            memory = stats;
+            round_result?
+        } else {
+            round()?
+        };
+
+        for (runs, duration) in accumulator.iter_mut().zip(durations) {
+            runs.push(duration);
+        }
+
+        if let Some(progress) = progress {
+            progress(accumulator[0].len(), durations[N - 1]);
+        }
+
+        if result.is_none() {
+            result = Some(round_result);
+        }
+    }
+
+    let stats = accumulator.map(|runs| {
+        if config.is_single() {
+            runs[0].into()
+        } else {
+            runs.into()
+        }
+    });
+
+    Ok((result.unwrap(), stats, memory))
+}
+
+/// Runtime of a part, split into parse and solve time for days that support it.
+pub struct Timings {
+    pub total: RuntimeStats,
+    pub phases: Option<(RuntimeStats, RuntimeStats)>,
+    /// Heap usage, when counting allocations
+    pub memory: Option<MemoryStats>,
+}
+
+impl std::fmt::Display for Timings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.total)?;
+
+        if let Some((parse, solve)) = &self.phases {
+            write!(
+                f,
+                "; parse: {}, solve: {}",
+                DurationFormatter(parse.median()),
+                DurationFormatter(solve.median()),
+            )?;
+        }
+
+        if let Some(memory) = &self.memory {
+            write!(f, "; {memory}")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -44,6 +269,11 @@ pub enum RuntimeStats {
         median: Duration,
         min: Duration,
         max: Duration,
+        mean: Duration,
+        std_dev: Duration,
+        p90: Duration,
+        p99: Duration,
+        outliers: usize,
     },
 }
 
@@ -61,6 +291,56 @@ impl RuntimeStats {
             Self::Multiple { median, .. } => *median,
         }
     }
+
+    pub fn min(&self) -> Duration {
+        match self {
+            Self::Single([duration]) => *duration,
+            Self::Multiple { min, .. } => *min,
+        }
+    }
+
+    pub fn max(&self) -> Duration {
+        match self {
+            Self::Single([duration]) => *duration,
+            Self::Multiple { max, .. } => *max,
+        }
+    }
+
+    pub fn mean(&self) -> Duration {
+        match self {
+            Self::Single([duration]) => *duration,
+            Self::Multiple { mean, .. } => *mean,
+        }
+    }
+
+    pub fn std_dev(&self) -> Duration {
+        match self {
+            Self::Single(_) => Duration::ZERO,
+            Self::Multiple { std_dev, .. } => *std_dev,
+        }
+    }
+
+    pub fn p90(&self) -> Duration {
+        match self {
+            Self::Single([duration]) => *duration,
+            Self::Multiple { p90, .. } => *p90,
+        }
+    }
+
+    pub fn p99(&self) -> Duration {
+        match self {
+            Self::Single([duration]) => *duration,
+            Self::Multiple { p99, .. } => *p99,
+        }
+    }
+
+    /// Number of runs outside of the Tukey fences.
+    pub fn outliers(&self) -> usize {
+        match self {
+            Self::Single(_) => 0,
+            Self::Multiple { outliers, .. } => *outliers,
+        }
+    }
 }
 
 impl From<Duration> for RuntimeStats {
@@ -79,12 +359,21 @@ impl From<Vec<Duration>> for RuntimeStats {
         let median = median(sorted.as_slice());
         let min = sorted[0];
         let max = sorted[sorted.len() - 1];
+        let (mean, std_dev) = mean_and_std_dev(sorted.as_slice());
+        let p90 = percentile(sorted.as_slice(), 90);
+        let p99 = percentile(sorted.as_slice(), 99);
+        let outliers = outliers(sorted.as_slice());
 
         Self::Multiple {
             runs: value,
             median,
             min,
             max,
+            mean,
+            std_dev,
+            p90,
+            p99,
+            outliers,
         }
     }
 }
@@ -94,14 +383,28 @@ impl std::fmt::Display for RuntimeStats {
         match self {
             Self::Single([duration]) => write!(f, "{}", DurationFormatter(*duration)),
             Self::Multiple {
-                min, max, median, ..
+                runs,
+                min,
+                max,
+                median,
+                mean,
+                std_dev,
+                p90,
+                p99,
+                outliers,
             } => {
                 write!(
                     f,
-                    "median: {}, min: {}, max: {}",
+                    "median: {}, mean: {} ± {}, min: {}, max: {}, p90: {}, p99: {}, outliers: {}/{}",
                     DurationFormatter(*median),
+                    DurationFormatter(*mean),
+                    DurationFormatter(*std_dev),
                     DurationFormatter(*min),
-                    DurationFormatter(*max)
+                    DurationFormatter(*max),
+                    DurationFormatter(*p90),
+                    DurationFormatter(*p99),
+                    outliers,
+                    runs.len(),
                 )
             }
         }
@@ -112,13 +415,56 @@ fn median(slice: &[Duration]) -> Duration {
     assert!(!slice.is_empty());
 
     let middle = slice.len() / 2;
-    if slice.len() % 2 == 0 {
+    if slice.len().is_multiple_of(2) {
         (slice[middle - 1] + slice[middle]) / 2
     } else {
         slice[middle]
     }
 }
 
+/// Nearest-rank percentile of a sorted slice.
+fn percentile(slice: &[Duration], percentile: usize) -> Duration {
+    assert!(!slice.is_empty());
+
+    let rank = (percentile * slice.len()).div_ceil(100);
+    slice[rank.clamp(1, slice.len()) - 1]
+}
+
+fn mean_and_std_dev(slice: &[Duration]) -> (Duration, Duration) {
+    assert!(!slice.is_empty());
+
+    let count = slice.len() as f64;
+    let mean = slice.iter().map(Duration::as_secs_f64).sum::<f64>() / count;
+
+    if slice.len() < 2 {
+        return (Duration::from_secs_f64(mean), Duration::ZERO);
+    }
+
+    let variance = slice
+        .iter()
+        .map(|duration| (duration.as_secs_f64() - mean).powi(2))
+        .sum::<f64>()
+        / (count - 1.0);
+
+    (
+        Duration::from_secs_f64(mean),
+        Duration::from_secs_f64(variance.sqrt()),
+    )
+}
+
+/// Counts the runs outside of the Tukey fences of a sorted slice.
+fn outliers(slice: &[Duration]) -> usize {
+    let q1 = percentile(slice, 25).as_secs_f64();
+    let q3 = percentile(slice, 75).as_secs_f64();
+    let fence = 1.5 * (q3 - q1);
+
+    slice
+        .iter()
+        .map(Duration::as_secs_f64)
+        .filter(|&run| run < q1 - fence || run > q3 + fence)
+        .count()
+}
+
 pub struct DurationFormatter(pub Duration);
 
 impl std::fmt::Display for DurationFormatter {
@@ -142,3 +488,64 @@ impl std::fmt::Display for DurationFormatter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn test_percentile() {
+        let runs = millis(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        assert_eq!(percentile(&runs, 90), Duration::from_millis(9));
+        assert_eq!(percentile(&runs, 99), Duration::from_millis(10));
+        assert_eq!(percentile(&runs, 25), Duration::from_millis(3));
+    }
+
+    #[test]
+    fn test_mean_and_std_dev() {
+        let runs = millis(&[2, 4, 4, 4, 5, 5, 7, 9]);
+        let (mean, std_dev) = mean_and_std_dev(&runs);
+        assert_eq!(mean, Duration::from_millis(5));
+        assert!((std_dev.as_secs_f64() - 0.002138).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_outliers() {
+        let runs = millis(&[10, 10, 11, 11, 12, 12, 13, 50]);
+        assert_eq!(outliers(&runs), 1);
+    }
+
+    #[test]
+    fn test_abandon() {
+        let rounds = Arc::new(AtomicUsize::new(0));
+        let counter = rounds.clone();
+        let config = BenchmarkConfig {
+            rounds: 1000,
+            ..Default::default()
+        };
+        let measured = measure(
+            move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(1));
+                Ok(0)
+            },
+            config,
+            None,
+        );
+
+        let abandonment = Abandonment::default();
+        let part = abandonment.clone();
+        let thread = std::thread::spawn(move || part.run(|| measured("")));
+
+        std::thread::sleep(Duration::from_millis(20));
+        abandonment.abandon();
+
+        assert!(thread.join().unwrap().is_err());
+        assert!(rounds.load(Ordering::SeqCst) < 1000);
+        assert!(!contended());
+    }
+}
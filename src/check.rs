@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use crate::{registry, AocError};
+
+/// Validates the inputs of every day, or of a single one, against the
+/// assumptions their solutions make, printing every check.
+pub fn check(inputs: &Path, day: Option<u32>) -> Result<(), AocError> {
+    let mut failures = 0;
+
+    for solution in registry::solutions() {
+        let puzzle = solution.id();
+        if puzzle == registry::TEMPLATE_ID || day.is_some_and(|day| day != puzzle) {
+            continue;
+        }
+
+        let path = inputs.join(format!("day-{puzzle:02}.txt"));
+        let input = match std::fs::read_to_string(&path) {
+            Ok(input) => input,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound && day.is_none() => {
+                continue;
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return Err(AocError::MissingInput { puzzle, path });
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        let checks = match solution.validate(&input) {
+            Ok(checks) => checks,
+            Err(error) => {
+                println!("Day {puzzle:02}: ERROR ({error})");
+                failures += 1;
+                continue;
+            }
+        };
+
+        if checks.is_empty() {
+            println!("Day {puzzle:02}: no assumptions to check");
+        }
+
+        for check in checks {
+            let status = if check.holds() { "OK" } else { "FAIL" };
+            println!("Day {puzzle:02} part {}: {status:<4} {check}", check.part);
+
+            if !check.holds() {
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(AocError::UnsupportedInput { failures });
+    }
+
+    Ok(())
+}
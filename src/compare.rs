@@ -0,0 +1,213 @@
+use std::time::Duration;
+
+use crate::{
+    benchmark::DurationFormatter,
+    report::{Entry, Report, Status},
+};
+
+/// Significance level under which a change in the run distributions is
+/// considered real rather than noise.
+const SIGNIFICANCE: f64 = 0.05;
+
+pub struct Comparison {
+    pub puzzle: u32,
+    pub part: u32,
+    pub baseline: f64,
+    pub current: f64,
+    /// Relative change of the median, in percent
+    pub change: f64,
+    /// Two-sided p-value of the Mann-Whitney U test, when both sides have
+    /// enough runs to compute it
+    pub p_value: Option<f64>,
+    pub verdict: Verdict,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Improvement,
+    Regression,
+    Unchanged,
+}
+
+/// Compares the medians of every day and part solved in both reports. A
+/// change is reported when it exceeds `threshold` percent and, if both reports
+/// have several runs, when the difference is statistically significant. Parts
+/// whose baseline median is zero have no relative change and are skipped.
+pub fn compare(baseline: &Report, current: &Report, threshold: f64) -> Vec<Comparison> {
+    current
+        .entries()
+        .filter_map(|entry| {
+            let previous = baseline.entry(entry.puzzle, entry.part)?;
+            if previous.status != Status::Solved || entry.status != Status::Solved {
+                return None;
+            }
+
+            compare_entries(previous, entry, threshold)
+        })
+        .collect()
+}
+
+fn compare_entries(baseline: &Entry, current: &Entry, threshold: f64) -> Option<Comparison> {
+    compare_runs(
+        current.puzzle,
+        current.part,
+        (baseline.median(), &baseline.runs),
+        (current.median(), &current.runs),
+        threshold,
+    )
+}
+
+/// Compares two measurements of a part, each given as its median and its
+/// runs in milliseconds. Returns `None` if the baseline median is zero, as a
+/// part too fast for the clock has no meaningful relative change.
+pub fn compare_runs(
+    puzzle: u32,
+    part: u32,
+    (baseline, baseline_runs): (f64, &[f64]),
+    (current, current_runs): (f64, &[f64]),
+    threshold: f64,
+) -> Option<Comparison> {
+    if baseline <= 0.0 {
+        return None;
+    }
+
+    let change = (current - baseline) / baseline * 100.0;
+    let p_value = mann_whitney_u(baseline_runs, current_runs);
+    let significant = p_value.is_none_or(|p| p < SIGNIFICANCE);
+
+    let verdict = if significant && change > threshold {
+        Verdict::Regression
+    } else if significant && change < -threshold {
+        Verdict::Improvement
+    } else {
+        Verdict::Unchanged
+    };
+
+    Some(Comparison {
+        puzzle,
+        part,
+        baseline,
+        current,
+        change,
+        p_value,
+        verdict,
+    })
+}
+
+impl std::fmt::Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Day {:02} part {}: {} -> {} ({:+.1}%",
+            self.puzzle,
+            self.part,
+            DurationFormatter(Duration::from_secs_f64(self.baseline / 1000.0)),
+            DurationFormatter(Duration::from_secs_f64(self.current / 1000.0)),
+            self.change,
+        )?;
+
+        if let Some(p_value) = self.p_value {
+            write!(f, ", p={p_value:.3}")?;
+        }
+
+        write!(f, ")")?;
+
+        match self.verdict {
+            Verdict::Improvement => write!(f, " improvement"),
+            Verdict::Regression => write!(f, " REGRESSION"),
+            Verdict::Unchanged => Ok(()),
+        }
+    }
+}
+
+/// Two-sided p-value of the Mann-Whitney U test, using the normal
+/// approximation. Returns `None` when either sample has fewer than two runs.
+fn mann_whitney_u(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() < 2 || b.len() < 2 {
+        return None;
+    }
+
+    let mut combined = a
+        .iter()
+        .map(|&value| (value, true))
+        .chain(b.iter().map(|&value| (value, false)))
+        .collect::<Vec<_>>();
+    combined.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    // Average the ranks of tied values
+    let mut rank_sum_a = 0.0;
+    let mut start = 0;
+    while start < combined.len() {
+        let mut end = start + 1;
+        while end < combined.len() && combined[end].0 == combined[start].0 {
+            end += 1;
+        }
+
+        let rank = (start + end + 1) as f64 / 2.0;
+        let count_a = combined[start..end]
+            .iter()
+            .filter(|(_, in_a)| *in_a)
+            .count();
+        rank_sum_a += rank * count_a as f64;
+        start = end;
+    }
+
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let u = rank_sum_a - n1 * (n1 + 1.0) / 2.0;
+    let mean = n1 * n2 / 2.0;
+    let std_dev = (n1 * n2 * (n1 + n2 + 1.0) / 12.0).sqrt();
+    let z = (u - mean) / std_dev;
+
+    Some((2.0 * (1.0 - standard_normal_cdf(z.abs()))).clamp(0.0, 1.0))
+}
+
+fn standard_normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Abramowitz and Stegun approximation 7.1.26 of the error function.
+fn erf(x: f64) -> f64 {
+    let sign = x.signum();
+    let x = x.abs();
+
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let polynomial = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+
+    sign * (1.0 - polynomial * (-x * x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mann_whitney_u_separated() {
+        let a = [1.0, 1.1, 1.2, 1.0, 1.1, 1.2, 1.0, 1.1];
+        let b = [2.0, 2.1, 2.2, 2.0, 2.1, 2.2, 2.0, 2.1];
+        let p = mann_whitney_u(&a, &b).unwrap();
+        assert!(p < 0.01, "{p}");
+    }
+
+    #[test]
+    fn test_mann_whitney_u_identical() {
+        let a = [1.0, 1.1, 1.2, 1.3, 1.4];
+        let p = mann_whitney_u(&a, &a).unwrap();
+        assert!(p > 0.9, "{p}");
+    }
+
+    #[test]
+    fn test_compare_runs() {
+        let comparison = compare_runs(1, 1, (2.0, &[]), (3.0, &[]), 5.0).unwrap();
+        assert_eq!(comparison.change, 50.0);
+        assert_eq!(comparison.verdict, Verdict::Regression);
+
+        assert!(compare_runs(1, 1, (0.0, &[0.0, 0.0]), (1.0, &[1.0, 1.0]), 5.0).is_none());
+    }
+
+    #[test]
+    fn test_mann_whitney_u_single_run() {
+        assert_eq!(mann_whitney_u(&[1.0], &[1.0, 2.0]), None);
+    }
+}
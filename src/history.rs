@@ -0,0 +1,334 @@
+use std::{
+    io::{BufRead, Write},
+    path::Path,
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    benchmark::DurationFormatter,
+    compare::{self, Verdict},
+    report::{Report, Status},
+    AocError,
+};
+
+/// Where, when and with what a benchmark ran.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Environment {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Abbreviated commit hash, when running from a git checkout
+    pub revision: Option<String>,
+    /// Whether the checkout had uncommitted changes
+    pub dirty: bool,
+    pub rustc: String,
+    pub host: String,
+}
+
+impl Environment {
+    pub fn collect(root: &Path) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+
+        let host = std::env::var("HOSTNAME")
+            .or_else(|_| std::env::var("COMPUTERNAME"))
+            .ok()
+            .or_else(|| command_output(Command::new("hostname")))
+            .unwrap_or_else(|| "unknown".to_string());
+
+        Self {
+            timestamp,
+            revision: git(root, &["rev-parse", "--short", "HEAD"]),
+            dirty: git(root, &["status", "--porcelain", "--untracked-files=no"])
+                .is_some_and(|status| !status.is_empty()),
+            rustc: env!("AOC2023_RUSTC_VERSION").to_string(),
+            host,
+        }
+    }
+}
+
+fn git(root: &Path, args: &[&str]) -> Option<String> {
+    let mut command = Command::new("git");
+    command.args(args).current_dir(root);
+    command_output(command)
+}
+
+fn command_output(mut command: Command) -> Option<String> {
+    let output = command
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    String::from_utf8(output.stdout)
+        .ok()
+        .map(|output| output.trim().to_string())
+}
+
+/// Benchmark of a single part, as stored in the history file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    #[serde(flatten)]
+    pub environment: Environment,
+    pub day: u32,
+    pub part: u32,
+    pub median_ms: f64,
+    pub runs_ms: Vec<f64>,
+}
+
+/// Appends every solved part of `report` to the JSON-lines history at `path`.
+pub fn append(
+    path: impl AsRef<Path>,
+    report: &Report,
+    environment: &Environment,
+) -> Result<(), AocError> {
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let mut writer = std::io::BufWriter::new(file);
+
+    for entry in report
+        .entries()
+        .filter(|entry| entry.status == Status::Solved)
+    {
+        let record = Record {
+            environment: environment.clone(),
+            day: entry.puzzle,
+            part: entry.part,
+            median_ms: entry.median(),
+            runs_ms: entry.runs.clone(),
+        };
+
+        serde_json::to_writer(&mut writer, &record)?;
+        writeln!(writer)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<Record>, AocError> {
+    let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+
+    let mut records = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record = serde_json::from_str(&line)
+            .map_err(|error| AocError::InvalidHistory(format!("line {}: {error}", index + 1)))?;
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Prints the recorded benchmarks of a day, or of one of its parts.
+pub fn show(
+    path: impl AsRef<Path>,
+    day: u32,
+    part: Option<u32>,
+    threshold: f64,
+) -> Result<(), AocError> {
+    let path = path.as_ref();
+    let records = match load(path) {
+        Ok(records) => records,
+        Err(AocError::Io(error)) if error.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(error) => return Err(error),
+    };
+
+    let mut found = false;
+    for current in [1, 2] {
+        if part.is_some_and(|part| part != current) {
+            continue;
+        }
+
+        let part_records = records
+            .iter()
+            .filter(|record| record.day == day && record.part == current)
+            .collect::<Vec<_>>();
+        if part_records.is_empty() {
+            continue;
+        }
+
+        found = true;
+        println!("Day {day:02} part {current}:");
+        for line in timeline(&part_records, threshold) {
+            println!("  {line}");
+        }
+    }
+
+    if !found {
+        println!("No benchmarks of day {day:02} in {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Renders how the median of a part changed from one benchmark to the next,
+/// oldest first, naming the revision behind every significant change.
+pub fn timeline(records: &[&Record], threshold: f64) -> Vec<String> {
+    let mut lines = Vec::with_capacity(records.len());
+
+    for (index, record) in records.iter().enumerate() {
+        let environment = &record.environment;
+        let revision = match (&environment.revision, environment.dirty) {
+            (Some(revision), false) => revision.clone(),
+            (Some(revision), true) => format!("{revision}+"),
+            (None, _) => "-".to_string(),
+        };
+
+        let mut line = format!(
+            "{}  {revision:<9} {:>10}",
+            Timestamp(environment.timestamp),
+            DurationFormatter(Duration::from_secs_f64(record.median_ms / 1000.0)).to_string(),
+        );
+
+        let previous = index.checked_sub(1).map(|index| records[index]);
+        let comparison = previous.and_then(|previous| {
+            compare::compare_runs(
+                record.day,
+                record.part,
+                (previous.median_ms, &previous.runs_ms),
+                (record.median_ms, &record.runs_ms),
+                threshold,
+            )
+        });
+
+        if let (Some(previous), Some(comparison)) = (previous, comparison) {
+            line += &format!("  {:+6.1}%", comparison.change);
+
+            let change = match comparison.verdict {
+                Verdict::Improvement => Some("improvement"),
+                Verdict::Regression => Some("REGRESSION"),
+                Verdict::Unchanged => None,
+            };
+
+            if let Some(change) = change {
+                line += &format!("  {change} {}", introduced_by(previous, record));
+            }
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Describes what changed between two benchmarks.
+fn introduced_by(previous: &Record, current: &Record) -> String {
+    let (before, after) = (&previous.environment, &current.environment);
+
+    let mut cause = match (&after.revision, after.dirty) {
+        (Some(revision), false) if before.revision.as_ref() != Some(revision) || before.dirty => {
+            format!("in {revision}")
+        }
+        (Some(revision), true) => format!("in uncommitted changes on {revision}"),
+        _ => "without a code change".to_string(),
+    };
+
+    if before.rustc != after.rustc {
+        cause += &format!(", compiler changed to {}", after.rustc);
+    }
+
+    if before.host != after.host {
+        cause += &format!(", host changed to {}", after.host);
+    }
+
+    cause
+}
+
+/// Unix timestamp, shown as a UTC date and time.
+struct Timestamp(u64);
+
+impl std::fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let days = (self.0 / 86400) as i64;
+        let seconds = self.0 % 86400;
+
+        // Civil date from days since the epoch, after Howard Hinnant's
+        // `civil_from_days`
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let day_of_era = z.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+            seconds / 3600,
+            seconds % 3600 / 60,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(timestamp: u64, revision: &str, runs_ms: Vec<f64>) -> Record {
+        let mut sorted = runs_ms.clone();
+        sorted.sort_by(f64::total_cmp);
+
+        Record {
+            environment: Environment {
+                timestamp,
+                revision: Some(revision.to_string()),
+                dirty: false,
+                rustc: "rustc 1.95.0".to_string(),
+                host: "bench".to_string(),
+            },
+            day: 5,
+            part: 1,
+            median_ms: sorted[sorted.len() / 2],
+            runs_ms,
+        }
+    }
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(Timestamp(0).to_string(), "1970-01-01 00:00");
+        assert_eq!(Timestamp(1701388800).to_string(), "2023-12-01 00:00");
+        assert_eq!(Timestamp(1709210096).to_string(), "2024-02-29 12:34");
+    }
+
+    #[test]
+    fn test_timeline() {
+        let records = [
+            record(1701388800, "aaaaaaa", vec![1.0, 1.1, 1.0, 1.1, 1.0, 1.1]),
+            record(1701392400, "bbbbbbb", vec![1.0, 1.1, 1.05, 1.1, 1.0, 1.05]),
+            record(1701396000, "ccccccc", vec![2.0, 2.1, 2.0, 2.1, 2.0, 2.1]),
+        ];
+        let records = records.iter().collect::<Vec<_>>();
+
+        let lines = timeline(&records, 5.0);
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("2023-12-01 00:00  aaaaaaa"));
+        assert!(!lines[1].contains("REGRESSION"), "{}", lines[1]);
+        assert!(lines[2].ends_with("REGRESSION in ccccccc"), "{}", lines[2]);
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        let record = record(1701388800, "aaaaaaa", vec![1.0, 2.0]);
+        let line = serde_json::to_string(&record).unwrap();
+
+        assert!(line.contains("\"revision\":\"aaaaaaa\""));
+        assert_eq!(serde_json::from_str::<Record>(&line).unwrap(), record);
+    }
+}
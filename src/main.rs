@@ -1,41 +1,317 @@
 use std::{
-    path::PathBuf,
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
     time::{Duration, Instant},
 };
 
-use benchmark::RuntimeStats;
-use clap::Parser;
+use aoc_util::{answer::Answer, error::SolveError, random::Rng};
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use itertools::Itertools;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
 use crate::{
-    benchmark::{measure, DurationFormatter},
-    puzzle::Puzzle,
-    report::Report,
+    answers::{Answers, Verdict},
+    benchmark::{BenchmarkConfig, DurationFormatter},
+    profile::{Profile, ProfileLayer},
+    puzzle::{Input, Outcome, Puzzle, Source},
+    report::{Report, Status},
 };
 
+mod answers;
 mod benchmark;
+mod check;
+mod compare;
+mod history;
+mod memory;
+mod profile;
 mod puzzle;
+mod registry;
 mod report;
+mod scaffold;
+mod tui;
+mod watch;
 
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Directory containing the day-XX.txt inputs and their named alternates
+    /// such as day-05.alt.txt
+    #[arg(
+        long,
+        id = "DIR",
+        env = "AOC2023_INPUTS",
+        default_value = "inputs",
+        global = true
+    )]
+    inputs_dir: PathBuf,
+    /// JSON-lines file every benchmark run is appended to
+    #[arg(
+        long,
+        id = "FILE",
+        env = "AOC2023_HISTORY",
+        default_value = "history.jsonl",
+        global = true
+    )]
+    history_file: PathBuf,
+    #[command(flatten)]
+    args: Args,
+}
+
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Create the crate of a new day from the template
+    New {
+        /// Day of the new puzzle
+        day: u32,
+        /// Title of the puzzle
+        #[arg(long)]
+        title: Option<String>,
+    },
+    /// Re-run the tests, examples and input of a day whenever its sources or
+    /// input change
+    Watch {
+        /// Day to watch
+        day: u32,
+        /// Polling interval
+        #[arg(long, id = "MILLIS", default_value_t = 500)]
+        interval: u64,
+    },
+    /// Browse all days with their latest answers and timings, running them on
+    /// a key press
+    Tui {
+        /// Benchmarking rounds
+        #[arg(short = 'r', long = "rounds", default_value_t = 10)]
+        rounds: u32,
+        /// Untimed warm-up rounds run before benchmarking
+        #[arg(short = 'w', long = "warmup", default_value_t = 1)]
+        warmup: u32,
+        /// Known answers file used to color the answers [default: answers.toml
+        /// in the inputs directory]
+        #[arg(long, id = "ANSWERS")]
+        answers: Option<PathBuf>,
+    },
+    /// Check the inputs against the assumptions the solutions make about them
+    Check {
+        /// Optional day to check
+        day: Option<u32>,
+    },
+    /// Show how the median time of a day changed over the recorded benchmarks
+    History {
+        /// Day to show
+        day: u32,
+        /// Optional part to show
+        #[arg(short, long)]
+        part: Option<u32>,
+        /// Relative change, in percent, above which a change is flagged
+        #[arg(long, id = "PERCENT", default_value_t = 5.0)]
+        threshold: f64,
+    },
+    /// Print a random input for a day, for stress tests and for benchmarking
+    /// how a day scales
+    Gen {
+        /// Day to generate an input for
+        day: u32,
+        /// Size of the input, such as the side of a grid or the number of lines
+        #[arg(long, default_value_t = 100)]
+        size: usize,
+        /// Seed of the generator, the same seed always giving the same input
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+}
+
+#[derive(clap::Args)]
 struct Args {
     /// Optional puzzle to run
     puzzle: Option<u32>,
     /// Optional part to run
     #[arg(short, long)]
     part: Option<u32>,
+    /// Input file of the puzzle, or - to read it from stdin
+    #[arg(short, long, id = "INPUT", requires = "puzzle")]
+    input: Option<PathBuf>,
     /// Benchmarking rounds
     #[arg(short = 'r', long = "rounds", default_value_t = 1)]
     rounds: u32,
+    /// Untimed warm-up rounds run before benchmarking
+    #[arg(short = 'w', long = "warmup", default_value_t = 0)]
+    warmup: u32,
+    /// Benchmark each part for this many seconds instead of a fixed number of rounds
+    #[arg(
+        short = 'b',
+        long = "budget",
+        id = "SECONDS",
+        conflicts_with = "rounds",
+        value_parser = parse_seconds
+    )]
+    budget: Option<Duration>,
     /// Optional benchmark report output location
     #[arg(short = 'o', long = "out", id = "PATH")]
     report: Option<PathBuf>,
+    /// Benchmark report format
+    #[arg(short, long, value_enum, default_value_t)]
+    format: report::Format,
+    /// Optional benchmark report to compare the results against
+    #[arg(long, id = "BASELINE")]
+    baseline: Option<PathBuf>,
+    /// Relative slowdown, in percent, above which a regression fails the run
+    #[arg(long, id = "PERCENT", default_value_t = 5.0)]
+    threshold: f64,
+    /// Check results against the known answers file
+    #[arg(long)]
+    verify: bool,
+    /// Known answers file used by --verify [default: answers.toml in the
+    /// inputs directory]
+    #[arg(long, id = "ANSWERS")]
+    answers: Option<PathBuf>,
+    /// Run the examples from the puzzle descriptions instead of the inputs
+    #[arg(short, long)]
+    examples: bool,
+    /// Give up on a part after this many seconds, including all its benchmark rounds
+    #[arg(short, long, id = "TIMEOUT", value_parser = parse_seconds)]
+    timeout: Option<Duration>,
+    /// Run up to this many puzzles concurrently
+    #[arg(short, long, id = "JOBS")]
+    jobs: Option<NonZeroUsize>,
+    /// Pin every job to its own core and give it a single-threaded pool, so
+    /// concurrent puzzles do not disturb each other's timings
+    #[arg(long, requires = "JOBS")]
+    isolate: bool,
+    /// Count the heap allocations of every part, reporting peak and total
+    /// bytes next to the timings. Requires the `memory` feature
+    #[arg(short, long)]
+    memory: bool,
+    /// Print how long the traced phases of every part took, as a tree of spans
+    #[arg(long)]
+    profile: bool,
+    /// Write the traced phases as folded stacks, for flamegraph tools
+    #[arg(long, id = "FOLDED")]
+    folded: Option<PathBuf>,
+    /// Do not append this benchmark to the history file
+    #[arg(long)]
+    no_history: bool,
 }
 
 fn main() -> anyhow::Result<()> {
-    trace();
+    let cli = Cli::parse();
+
+    let profile = (cli.args.profile || cli.args.folded.is_some()).then(Profile::default);
+    trace(profile.as_ref().map(Profile::layer));
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    match cli.command {
+        Some(Command::New { day, title }) => {
+            scaffold::new_day(root, &cli.inputs_dir, day, title.as_deref())?;
+            Ok(())
+        }
+        Some(Command::Watch { day, interval }) => {
+            let interval = Duration::from_millis(interval);
+            watch::watch(root, &cli.inputs_dir, day, interval)?;
+            Ok(())
+        }
+        Some(Command::Tui {
+            rounds,
+            warmup,
+            answers,
+        }) => {
+            let answers = answers.unwrap_or_else(|| cli.inputs_dir.join(answers::FILE_NAME));
+            let answers = match answers.exists() {
+                true => Answers::load(&answers)?,
+                false => Answers::default(),
+            };
+            let benchmark = BenchmarkConfig {
+                rounds,
+                warmup,
+                budget: None,
+            };
+            tui::tui(&cli.inputs_dir, answers, benchmark)?;
+            Ok(())
+        }
+        Some(Command::Check { day }) => {
+            check::check(&cli.inputs_dir, day)?;
+            Ok(())
+        }
+        Some(Command::History {
+            day,
+            part,
+            threshold,
+        }) => {
+            history::show(&cli.history_file, day, part, threshold)?;
+            Ok(())
+        }
+        Some(Command::Gen { day, size, seed }) => {
+            let solution = registry::solutions()
+                .find(|solution| solution.id() == day && day != registry::TEMPLATE_ID)
+                .ok_or(AocError::NoSuchPuzzle { puzzle: day })?;
+            let input = solution
+                .generate(size, &mut Rng::new(seed))
+                .ok_or(AocError::NoGenerator { puzzle: day })?;
+            print!("{input}");
+            Ok(())
+        }
+        None => run(cli.args, cli.inputs_dir, root, &cli.history_file, profile),
+    }
+}
+
+fn run(
+    args: Args,
+    inputs_dir: PathBuf,
+    root: &Path,
+    history_file: &Path,
+    profile: Option<Profile>,
+) -> anyhow::Result<()> {
+    let jobs = args.jobs.map_or(1, NonZeroUsize::get);
+
+    if jobs > 1 && !args.isolate && (args.rounds > 1 || args.budget.is_some()) {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "benchmarking with --jobs requires --isolate",
+            )
+            .exit();
+    }
+
+    if jobs > 1 && args.memory {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "counting allocations with --memory requires a single job",
+            )
+            .exit();
+    }
+
+    if args.memory && !cfg!(feature = "memory") {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "counting allocations with --memory requires building with --features memory",
+            )
+            .exit();
+    }
+
+    // A timed out part keeps allocating in the background, into the counts of
+    // the following parts
+    if args.memory && args.timeout.is_some() {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "counting allocations with --memory cannot be combined with --timeout",
+            )
+            .exit();
+    }
+
+    if args.memory {
+        memory::enable();
+    }
 
-    let args = Args::parse();
     let parts = match args.part {
         Some(1) => [true, false],
         Some(2) => [false, true],
@@ -43,192 +319,132 @@ fn main() -> anyhow::Result<()> {
         _ => [false, false],
     };
 
-    let rounds = args.rounds;
-
-    let puzzles = [
-        Puzzle::new(
-            0,
-            "inputs/template.txt",
-            measure(aoc2023_template::part1, rounds),
-            measure(aoc2023_template::part2, rounds),
-        ),
-        Puzzle::new(
-            1,
-            "inputs/day-01.txt",
-            measure(aoc2023_01::part1, rounds),
-            measure(aoc2023_01::part2, rounds),
-        ),
-        Puzzle::new(
-            2,
-            "inputs/day-02.txt",
-            measure(aoc2023_02::part1, rounds),
-            measure(aoc2023_02::part2, rounds),
-        ),
-        Puzzle::new(
-            3,
-            "inputs/day-03.txt",
-            measure(aoc2023_03::part1, rounds),
-            measure(aoc2023_03::part2, rounds),
-        ),
-        Puzzle::new(
-            4,
-            "inputs/day-04.txt",
-            measure(aoc2023_04::part1, rounds),
-            measure(aoc2023_04::part2, rounds),
-        ),
-        Puzzle::new(
-            5,
-            "inputs/day-05.txt",
-            measure(aoc2023_05::part1, rounds),
-            measure(aoc2023_05::part2, rounds),
-        ),
-        Puzzle::new(
-            6,
-            "inputs/day-06.txt",
-            measure(aoc2023_06::part1, rounds),
-            measure(aoc2023_06::part2, rounds),
-        ),
-        Puzzle::new(
-            7,
-            "inputs/day-07.txt",
-            measure(aoc2023_07::part1, rounds),
-            measure(aoc2023_07::part2, rounds),
-        ),
-        Puzzle::new(
-            8,
-            "inputs/day-08.txt",
-            measure(aoc2023_08::part1, rounds),
-            measure(aoc2023_08::part2, rounds),
-        ),
-        Puzzle::new(
-            9,
-            "inputs/day-09.txt",
-            measure(aoc2023_09::part1, rounds),
-            measure(aoc2023_09::part2, rounds),
-        ),
-        Puzzle::new(
-            10,
-            "inputs/day-10.txt",
-            measure(aoc2023_10::part1, rounds),
-            measure(aoc2023_10::part2, rounds),
-        ),
-        Puzzle::new(
-            11,
-            "inputs/day-11.txt",
-            measure(aoc2023_11::part1, rounds),
-            measure(aoc2023_11::part2, rounds),
-        ),
-        Puzzle::new(
-            12,
-            "inputs/day-12.txt",
-            measure(aoc2023_12::part1, rounds),
-            measure(aoc2023_12::part2, rounds),
-        ),
-        Puzzle::new(
-            13,
-            "inputs/day-13.txt",
-            measure(aoc2023_13::part1, rounds),
-            measure(aoc2023_13::part2, rounds),
-        ),
-        Puzzle::new(
-            14,
-            "inputs/day-14.txt",
-            measure(aoc2023_14::part1, rounds),
-            measure(aoc2023_14::part2, rounds),
-        ),
-        Puzzle::new(
-            15,
-            "inputs/day-15.txt",
-            measure(aoc2023_15::part1, rounds),
-            measure(aoc2023_15::part2, rounds),
-        ),
-        Puzzle::new(
-            16,
-            "inputs/day-16.txt",
-            measure(aoc2023_16::part1, rounds),
-            measure(aoc2023_16::part2, rounds),
-        ),
-        Puzzle::new(
-            17,
-            "inputs/day-17.txt",
-            measure(aoc2023_17::part1, rounds),
-            measure(aoc2023_17::part2, rounds),
-        ),
-        Puzzle::new(
-            18,
-            "inputs/day-18.txt",
-            measure(aoc2023_18::part1, rounds),
-            measure(aoc2023_18::part2, rounds),
-        ),
-        Puzzle::new(
-            19,
-            "inputs/day-19.txt",
-            measure(aoc2023_19::part1, rounds),
-            measure(aoc2023_19::part2, rounds),
-        ),
-        Puzzle::new(
-            20,
-            "inputs/day-20.txt",
-            measure(aoc2023_20::part1, rounds),
-            measure(aoc2023_20::part2, rounds),
-        ),
-        Puzzle::new(
-            21,
-            "inputs/day-21.txt",
-            measure(aoc2023_21::part1, rounds),
-            measure(aoc2023_21::part2, rounds),
-        ),
-        Puzzle::new(
-            22,
-            "inputs/day-22.txt",
-            measure(aoc2023_22::part1, rounds),
-            measure(aoc2023_22::part2, rounds),
-        ),
-        Puzzle::new(
-            23,
-            "inputs/day-23.txt",
-            measure(aoc2023_23::part1, rounds),
-            measure(aoc2023_23::part2, rounds),
-        ),
-        Puzzle::new(
-            24,
-            "inputs/day-24.txt",
-            measure(aoc2023_24::part1, rounds),
-            measure(aoc2023_24::part2, rounds),
-        ),
-        Puzzle::new(
-            25,
-            "inputs/day-25.txt",
-            measure(aoc2023_25::part1, rounds),
-            measure(aoc2023_25::part2, rounds),
-        ),
-    ];
+    let benchmark = BenchmarkConfig {
+        rounds: args.rounds,
+        warmup: args.warmup,
+        budget: args.budget,
+    };
+
+    let puzzles = registry::solutions()
+        .map(|solution| {
+            let input = match (&args.input, args.puzzle) {
+                (Some(path), Some(puzzle)) if puzzle == solution.id() => Input::from(path.clone()),
+                _ => Input::Directory(inputs_dir.clone()),
+            };
+
+            Puzzle::new(solution, benchmark, input, args.timeout)
+        })
+        .collect_vec();
 
     let start = Instant::now();
 
-    let mut report = args.report.as_ref().map(|_| Report::default());
+    let baseline = args.baseline.as_ref().map(Report::load).transpose()?;
+    // Only benchmarks of the real inputs are comparable over time
+    let record =
+        !benchmark.is_single() && !args.examples && args.input.is_none() && !args.no_history;
+    let mut report = (args.report.is_some() || baseline.is_some() || record).then(Report::default);
+    let answers = args
+        .verify
+        .then(|| {
+            let path = args
+                .answers
+                .unwrap_or_else(|| inputs_dir.join(answers::FILE_NAME));
+            Answers::load(path)
+        })
+        .transpose()?;
+    let mut failures = Vec::new();
 
     let mut sum_of_medians = Duration::ZERO;
-    let visitor = |puzzle, part, stats: RuntimeStats, result| {
-        println!("Day {puzzle:02} part {part} ({stats}): {result}");
-        sum_of_medians += stats.median();
+    let visitor = |puzzle, part, source: Source, outcome: Outcome| {
+        let (stats, answer) = match outcome {
+            Outcome::Solved { timings, answer } => (timings, answer),
+            Outcome::Failed { error, snippet } => {
+                println!("Day {puzzle:02} part {part}{source}: ERROR ({error})");
+                if let Some(snippet) = snippet {
+                    println!("{snippet}");
+                }
+                failures.push(AocError::Solve {
+                    puzzle,
+                    part,
+                    error,
+                });
+                if let (Source::Input { name: None }, Some(report)) = (source, report.as_mut()) {
+                    report.push_failure(puzzle, part, Status::Failed);
+                }
+                return Ok(());
+            }
+            Outcome::Panicked { message } => {
+                println!("Day {puzzle:02} part {part}{source}: PANIC ({message})");
+                failures.push(AocError::Panic {
+                    puzzle,
+                    part,
+                    message,
+                });
+                if let (Source::Input { name: None }, Some(report)) = (source, report.as_mut()) {
+                    report.push_failure(puzzle, part, Status::Panicked);
+                }
+                return Ok(());
+            }
+            Outcome::TimedOut { timeout } => {
+                println!(
+                    "Day {puzzle:02} part {part}{source}: TIMEOUT (after {})",
+                    DurationFormatter(timeout)
+                );
+                failures.push(AocError::Timeout {
+                    puzzle,
+                    part,
+                    timeout,
+                });
+                if let (Source::Input { name: None }, Some(report)) = (source, report.as_mut()) {
+                    report.push_failure(puzzle, part, Status::TimedOut);
+                }
+                return Ok(());
+            }
+        };
 
-        if let Some(report) = report.as_mut() {
-            report.push_entry(puzzle, part, &stats);
+        let verdict = match &source {
+            Source::Input { name: None } => answers
+                .as_ref()
+                .map(|answers| answers.verify(puzzle, part, &answer)),
+            Source::Input { name: Some(_) } => None,
+            Source::Example { expected, .. } => Some(Verdict::new(Some(expected), &answer)),
+        };
+
+        if let Some(verdict) = verdict {
+            println!("Day {puzzle:02} part {part}{source} ({stats}): {answer} [{verdict}]");
+
+            if let Verdict::Fail { expected } = verdict {
+                failures.push(AocError::WrongAnswer {
+                    puzzle,
+                    part,
+                    expected,
+                    actual: answer.clone(),
+                });
+            }
+        } else {
+            println!("Day {puzzle:02} part {part}{source} ({stats}): {answer}");
+        }
+
+        sum_of_medians += stats.total.median();
+
+        if let (Source::Input { name: None }, Some(report)) = (source, report.as_mut()) {
+            report.push_entry(puzzle, part, &stats, &answer);
         }
 
         Ok(())
     };
 
     if let Some(puzzle) = args.puzzle {
-        run_one(puzzle, &puzzles, parts, visitor)?;
+        run_one(puzzle, &puzzles, parts, args.examples, visitor)?;
+    } else if jobs > 1 {
+        run_parallel(&puzzles, parts, args.examples, jobs, args.isolate, visitor)?;
     } else {
-        run_all(&puzzles, parts, visitor)?;
+        run_all(&puzzles, parts, args.examples, visitor)?;
     }
 
     let total = start.elapsed();
 
-    if rounds > 1 {
+    if !benchmark.is_single() {
         println!(
             "Sum of median solve times: {}",
             DurationFormatter(sum_of_medians),
@@ -237,10 +453,46 @@ fn main() -> anyhow::Result<()> {
         println!("Sum of solve times: {}", DurationFormatter(sum_of_medians),);
     }
 
-    println!("Total time: {}", DurationFormatter(total));
+    println!("Wall-clock time: {}", DurationFormatter(total));
 
-    if let Some(report) = report {
-        report.save_to(args.report.unwrap())?;
+    if let (Some(baseline), Some(report)) = (baseline.as_ref(), report.as_ref()) {
+        println!("Compared to baseline:");
+
+        for comparison in compare::compare(baseline, report, args.threshold) {
+            println!("{comparison}");
+
+            if comparison.verdict == compare::Verdict::Regression {
+                failures.push(AocError::Regression {
+                    puzzle: comparison.puzzle,
+                    part: comparison.part,
+                    change: comparison.change,
+                    threshold: args.threshold,
+                });
+            }
+        }
+    }
+
+    if let (true, Some(report)) = (record, report.as_ref()) {
+        history::append(history_file, report, &history::Environment::collect(root))?;
+    }
+
+    if let (Some(report), Some(path)) = (report, args.report.as_ref()) {
+        report.save_to(path, args.format)?;
+    }
+
+    if let Some(profile) = profile {
+        if args.profile {
+            println!("Profile:");
+            print!("{}", profile.tree());
+        }
+
+        if let Some(path) = args.folded.as_ref() {
+            profile.save_folded(path)?;
+        }
+    }
+
+    if let Some(failure) = failures.into_iter().next() {
+        return Err(failure.into());
     }
 
     Ok(())
@@ -250,9 +502,69 @@ fn main() -> anyhow::Result<()> {
 pub enum AocError {
     #[error("No puzzle {puzzle}")]
     NoSuchPuzzle { puzzle: u32 },
+    #[error("Wrong answer for day {puzzle:02} part {part}: expected {expected}, got {actual}")]
+    WrongAnswer {
+        puzzle: u32,
+        part: u32,
+        expected: Answer,
+        actual: Answer,
+    },
+    #[error("Day {puzzle:02} part {part} failed: {error}")]
+    Solve {
+        puzzle: u32,
+        part: u32,
+        error: SolveError,
+    },
+    #[error("Day {puzzle:02} part {part} panicked: {message}")]
+    Panic {
+        puzzle: u32,
+        part: u32,
+        message: String,
+    },
+    #[error("Day {puzzle:02} part {part} timed out after {}", DurationFormatter(*timeout))]
+    Timeout {
+        puzzle: u32,
+        part: u32,
+        timeout: Duration,
+    },
+    #[error("Day {puzzle:02} part {part} regressed by {change:.1}% (threshold {threshold}%)")]
+    Regression {
+        puzzle: u32,
+        part: u32,
+        change: f64,
+        threshold: f64,
+    },
+    #[error("No input for day {puzzle:02}, expected it at {}", path.display())]
+    MissingInput { puzzle: u32, path: PathBuf },
+    #[error("{} already exists", path.display())]
+    DayExists { path: PathBuf },
+    #[error("Template crate no longer matches the scaffold generator")]
+    InvalidTemplate,
+    #[error("Invalid manifest: {0}")]
+    InvalidManifest(&'static str),
+    #[error("Cannot isolate {jobs} jobs on {cores} cores")]
+    NotEnoughCores { jobs: usize, cores: usize },
+    #[error("Invalid benchmark report: {0}")]
+    InvalidReport(String),
+    #[error("{format} reports cannot be read back, use a CSV or JSON report as the baseline")]
+    UnreadableReport { format: &'static str },
+    #[error("Invalid answers file: {0}")]
+    InvalidAnswers(String),
+    #[error("{failures} of the input checks failed")]
+    UnsupportedInput { failures: usize },
+    #[error("Day {puzzle:02} has no input generator")]
+    NoGenerator { puzzle: u32 },
+    #[error("Invalid benchmark history: {0}")]
+    InvalidHistory(String),
+    #[error(transparent)]
+    Answers(#[from] toml::de::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
     #[error(transparent)]
     Report(csv::Error),
     #[error(transparent)]
+    ThreadPool(#[from] rayon::ThreadPoolBuildError),
+    #[error(transparent)]
     Io(#[from] std::io::Error),
 }
 
@@ -275,31 +587,145 @@ impl From<csv::Error> for AocError {
 fn run_all(
     puzzles: &[Puzzle],
     parts: [bool; 2],
-    mut visitor: impl FnMut(u32, u32, RuntimeStats, String) -> Result<(), AocError>,
+    examples: bool,
+    mut visitor: impl FnMut(u32, u32, Source, Outcome) -> Result<(), AocError>,
 ) -> Result<(), AocError> {
-    for puzzle in puzzles[1..].iter() {
-        puzzle.run(parts, &mut visitor)?;
+    for puzzle in puzzles
+        .iter()
+        .filter(|puzzle| puzzle.id() != registry::TEMPLATE_ID)
+    {
+        puzzle.run(parts, examples, &mut visitor)?;
     }
 
     Ok(())
 }
 
+/// Runs the puzzles on `jobs` worker threads, passing the results to the
+/// visitor in puzzle order as soon as all earlier puzzles are done.
+fn run_parallel(
+    puzzles: &[Puzzle],
+    parts: [bool; 2],
+    examples: bool,
+    jobs: usize,
+    isolate: bool,
+    mut visitor: impl FnMut(u32, u32, Source, Outcome) -> Result<(), AocError>,
+) -> Result<(), AocError> {
+    let puzzles = puzzles
+        .iter()
+        .filter(|puzzle| puzzle.id() != registry::TEMPLATE_ID)
+        .collect_vec();
+
+    // Each isolated job gets a single-threaded pool pinned to its own core,
+    // which also catches the parallel iterators used inside the puzzles.
+    let pools = if isolate {
+        let cores = core_affinity::get_core_ids().unwrap_or_default();
+        if cores.len() < jobs {
+            return Err(AocError::NotEnoughCores {
+                jobs,
+                cores: cores.len(),
+            });
+        }
+
+        cores
+            .into_iter()
+            .take(jobs)
+            .map(|core| {
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(1)
+                    .start_handler(move |_| {
+                        core_affinity::set_for_current(core);
+                    })
+                    .build()
+                    .map(Some)
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        (0..jobs).map(|_| None).collect_vec()
+    };
+
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::scope(|scope| {
+        for pool in pools.iter() {
+            let (puzzles, next, sender) = (&puzzles, &next, sender.clone());
+
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(puzzle) = puzzles.get(index) else {
+                    return;
+                };
+
+                let run = || {
+                    let mut results = Vec::new();
+                    puzzle
+                        .run(parts, examples, |puzzle, part, source, outcome| {
+                            results.push((puzzle, part, source, outcome));
+                            Ok(())
+                        })
+                        .map(|_| results)
+                };
+
+                let results = match pool.as_ref() {
+                    Some(pool) => pool.install(run),
+                    None => run(),
+                };
+
+                if sender.send((index, results)).is_err() {
+                    return;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut expected = 0;
+
+        for (index, results) in receiver {
+            pending.insert(index, results);
+
+            while let Some(results) = pending.remove(&expected) {
+                for (puzzle, part, source, outcome) in results? {
+                    visitor(puzzle, part, source, outcome)?;
+                }
+                expected += 1;
+            }
+        }
+
+        Ok(())
+    })
+}
+
 fn run_one(
     puzzle: u32,
     puzzles: &[Puzzle],
     parts: [bool; 2],
-    visitor: impl FnMut(u32, u32, RuntimeStats, String) -> Result<(), AocError>,
+    examples: bool,
+    visitor: impl FnMut(u32, u32, Source, Outcome) -> Result<(), AocError>,
 ) -> Result<(), AocError> {
     let puzzle = puzzles
-        .get(puzzle as usize)
+        .iter()
+        .find(|candidate| candidate.id() == puzzle)
         .ok_or(AocError::NoSuchPuzzle { puzzle })?;
 
-    puzzle.run(parts, visitor)
+    puzzle.run(parts, examples, visitor)
+}
+
+/// Parses a positive, finite number of seconds.
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    let seconds = value.parse::<f64>().map_err(|err| err.to_string())?;
+    if !seconds.is_finite() || seconds <= 0.0 {
+        return Err("expected a positive number of seconds".to_string());
+    }
+
+    Duration::try_from_secs_f64(seconds).map_err(|err| err.to_string())
 }
 
-pub fn trace() {
+/// Installs the global subscriber. The log filter only applies to the
+/// printed events, so the profile layer still sees every span.
+pub fn trace(profile: Option<ProfileLayer>) {
     tracing_subscriber::registry()
-        .with(fmt::layer())
-        .with(EnvFilter::from_env("AOC2023_LOG"))
+        .with(fmt::layer().with_filter(EnvFilter::from_env("AOC2023_LOG")))
+        .with(profile)
         .init();
 }
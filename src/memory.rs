@@ -0,0 +1,178 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static TOTAL: AtomicUsize = AtomicUsize::new(0);
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// The counting allocator is only installed with the `memory` feature, so
+/// that other builds do not pay for an atomic load on every allocation.
+#[cfg(feature = "memory")]
+mod allocator {
+    use std::alloc::{GlobalAlloc, Layout, System};
+
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    /// System allocator that counts heap usage once [`enable`]d.
+    pub struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            record_dealloc(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                record_dealloc(layout.size());
+                record_alloc(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    fn record_alloc(size: usize) {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+
+        COUNT.fetch_add(1, Ordering::Relaxed);
+        TOTAL.fetch_add(size, Ordering::Relaxed);
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(size: usize) {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // Memory allocated before counting was enabled is not tracked
+        let _ = CURRENT.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+            Some(current.saturating_sub(size))
+        });
+    }
+}
+
+/// Starts counting allocations. Counts are process-wide, so they are only
+/// meaningful while a single part runs at a time.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Heap usage of a part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryStats {
+    /// Highest number of live bytes above what was live before the part ran
+    pub peak: usize,
+    /// Bytes allocated in total, including reallocations
+    pub allocated: usize,
+    pub allocations: usize,
+}
+
+/// Runs `function`, counting what it allocates on any thread if counting is
+/// enabled.
+pub fn track<R>(function: impl FnOnce() -> R) -> (R, Option<MemoryStats>) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return (function(), None);
+    }
+
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let allocated = TOTAL.load(Ordering::Relaxed);
+    let allocations = COUNT.load(Ordering::Relaxed);
+
+    let result = function();
+
+    let stats = MemoryStats {
+        peak: PEAK.load(Ordering::Relaxed).saturating_sub(baseline),
+        allocated: TOTAL.load(Ordering::Relaxed) - allocated,
+        allocations: COUNT.load(Ordering::Relaxed) - allocations,
+    };
+
+    (result, Some(stats))
+}
+
+impl std::fmt::Display for MemoryStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "peak: {}, allocated: {} in {} allocations",
+            ByteFormatter(self.peak),
+            ByteFormatter(self.allocated),
+            self.allocations,
+        )
+    }
+}
+
+pub struct ByteFormatter(pub usize);
+
+impl std::fmt::Display for ByteFormatter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+        if self.0 < 1024 {
+            return write!(f, "{}B", self.0);
+        }
+
+        let mut value = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while value >= 1024.0 && unit + 1 < UNITS.len() {
+            value /= 1024.0;
+            unit += 1;
+        }
+
+        write!(f, "{value:.1}{}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "memory")]
+    fn test_track() {
+        enable();
+
+        let (vector, stats) = track(|| {
+            let mut vector = Vec::<u8>::with_capacity(4096);
+            vector.push(1);
+            std::hint::black_box(Vec::<u8>::with_capacity(1024));
+            vector
+        });
+
+        let stats = stats.unwrap();
+        assert!(stats.allocations >= 2);
+        assert!(stats.allocated >= 5120);
+        assert_eq!(vector, [1]);
+    }
+
+    #[test]
+    fn test_byte_formatter() {
+        assert_eq!(ByteFormatter(512).to_string(), "512B");
+        assert_eq!(ByteFormatter(1536).to_string(), "1.5KiB");
+        assert_eq!(ByteFormatter(3 * 1024 * 1024).to_string(), "3.0MiB");
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id},
+    Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+use crate::benchmark::DurationFormatter;
+
+/// Time spent in every distinct stack of spans, collected by a
+/// [`ProfileLayer`].
+#[derive(Clone, Default)]
+pub struct Profile {
+    stacks: Arc<Mutex<HashMap<Vec<String>, Totals>>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Totals {
+    /// Position of the first span with this stack, to print stacks in the
+    /// order they first ran
+    order: usize,
+    busy: Duration,
+    calls: usize,
+}
+
+impl Profile {
+    pub fn layer(&self) -> ProfileLayer {
+        ProfileLayer {
+            profile: self.clone(),
+        }
+    }
+
+    /// Stacks together with their own time, excluding the time spent in
+    /// nested spans, in the order they first ran.
+    fn self_times(&self) -> Vec<(Vec<String>, Duration)> {
+        let stacks = self.stacks.lock().unwrap();
+
+        let mut self_times: HashMap<&[String], Duration> = stacks
+            .iter()
+            .map(|(stack, totals)| (stack.as_slice(), totals.busy))
+            .collect();
+
+        for (stack, totals) in stacks.iter() {
+            if let Some((_, parent)) = stack.split_last() {
+                if let Some(time) = self_times.get_mut(parent) {
+                    *time = time.saturating_sub(totals.busy);
+                }
+            }
+        }
+
+        let mut result = self_times
+            .into_iter()
+            .map(|(stack, time)| (stacks[stack].order, stack.to_vec(), time))
+            .collect::<Vec<_>>();
+        result.sort();
+
+        result
+            .into_iter()
+            .map(|(_, stack, time)| (stack, time))
+            .collect()
+    }
+
+    /// Renders the stacks as an indented tree of total times and call counts.
+    pub fn tree(&self) -> String {
+        let stacks = self.stacks.lock().unwrap();
+
+        let mut ordered = stacks.iter().collect::<Vec<_>>();
+        ordered.sort_by_key(|(_, totals)| totals.order);
+
+        let mut tree = String::new();
+        render(&ordered, &[], &mut tree);
+        tree
+    }
+
+    /// Writes the stacks in the folded format read by flamegraph tools, one
+    /// `root;child;grandchild <microseconds>` line per stack.
+    pub fn save_folded(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+        for (stack, time) in self.self_times() {
+            writeln!(writer, "{} {}", stack.join(";"), time.as_micros())?;
+        }
+
+        writer.flush()
+    }
+}
+
+fn render(ordered: &[(&Vec<String>, &Totals)], parent: &[String], tree: &mut String) {
+    for (stack, totals) in ordered.iter() {
+        let Some((label, prefix)) = stack.split_last() else {
+            continue;
+        };
+
+        if prefix != parent {
+            continue;
+        }
+
+        let _ = writeln!(
+            tree,
+            "{}{label}: {} ({} {})",
+            "  ".repeat(prefix.len()),
+            DurationFormatter(totals.busy),
+            totals.calls,
+            if totals.calls == 1 { "call" } else { "calls" },
+        );
+
+        render(ordered, stack, tree);
+    }
+}
+
+/// Layer that measures how long every span is entered and adds it to a
+/// [`Profile`] once the span closes.
+pub struct ProfileLayer {
+    profile: Profile,
+}
+
+/// Name of a span followed by its fields.
+struct Label(String);
+
+#[derive(Default)]
+struct Timing {
+    busy: Duration,
+    entered: Option<Instant>,
+}
+
+impl<S> Layer<S> for ProfileLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attributes: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut visitor = LabelVisitor(span.name().to_string());
+        attributes.record(&mut visitor);
+
+        let mut extensions = span.extensions_mut();
+        extensions.insert(Label(visitor.0));
+        extensions.insert(Timing::default());
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<Timing>() {
+                timing.entered = Some(Instant::now());
+            }
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<Timing>() {
+                if let Some(entered) = timing.entered.take() {
+                    timing.busy += entered.elapsed();
+                }
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(scope) = ctx.span_scope(&id) else {
+            return;
+        };
+
+        let mut busy = Duration::ZERO;
+        let mut stack = Vec::new();
+        for (depth, span) in scope.enumerate() {
+            let extensions = span.extensions();
+
+            if depth == 0 {
+                busy = extensions
+                    .get::<Timing>()
+                    .map_or(Duration::ZERO, |timing| timing.busy);
+            }
+
+            let label = extensions
+                .get::<Label>()
+                .map_or_else(|| span.name().to_string(), |label| label.0.clone());
+            stack.push(label);
+        }
+        stack.reverse();
+
+        let mut stacks = self.profile.stacks.lock().unwrap();
+        let order = stacks.len();
+        let totals = stacks.entry(stack).or_insert(Totals {
+            order,
+            ..Default::default()
+        });
+        totals.busy += busy;
+        totals.calls += 1;
+    }
+}
+
+struct LabelVisitor(String);
+
+impl Visit for LabelVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        let _ = write!(self.0, " {}={value:?}", field.name());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_profile() {
+        let profile = Profile::default();
+        let subscriber = tracing_subscriber::registry().with(profile.layer());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _puzzle = tracing::info_span!("puzzle", day = 5, part = 1).entered();
+            for _ in 0..2 {
+                let _parse = tracing::info_span!("parse").entered();
+            }
+            let _search = tracing::info_span!("search").entered();
+        });
+
+        let stacks = profile
+            .self_times()
+            .into_iter()
+            .map(|(stack, _)| stack.join(";"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stacks,
+            [
+                "puzzle day=5 part=1;parse",
+                "puzzle day=5 part=1;search",
+                "puzzle day=5 part=1"
+            ]
+        );
+
+        let tree = profile.tree();
+        let lines = tree.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("  parse: "));
+        assert!(lines[1].ends_with("(2 calls)"));
+    }
+}
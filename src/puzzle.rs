@@ -1,48 +1,416 @@
-use std::path::{Path, PathBuf};
+use std::{
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    thread,
+    time::Duration,
+};
 
-use crate::{benchmark::RuntimeStats, AocError};
+use aoc_util::{answer::Answer, error::SolveError, solution::DynSolution};
+
+use crate::{
+    benchmark::{measure, measure_phases, Abandonment, BenchmarkConfig, Progress, Timings},
+    AocError,
+};
+
+type Measured = Arc<dyn Fn(&str) -> Result<(Timings, Answer), SolveError> + Send + Sync>;
 
-#[allow(clippy::type_complexity)]
 pub struct Puzzle {
     puzzle: u32,
-    input_file: PathBuf,
-    p1: Box<dyn Fn(&str) -> (RuntimeStats, String)>,
-    p2: Box<dyn Fn(&str) -> (RuntimeStats, String)>,
+    input: Input,
+    benchmark: BenchmarkConfig,
+    timeout: Option<Duration>,
+    solution: &'static dyn DynSolution,
+    p1: Measured,
+    p2: Measured,
 }
 
 impl Puzzle {
-    #[allow(clippy::type_complexity)]
     pub fn new(
-        id: u32,
-        input_file: impl AsRef<Path>,
-        p1: Box<dyn Fn(&str) -> (RuntimeStats, String)>,
-        p2: Box<dyn Fn(&str) -> (RuntimeStats, String)>,
+        solution: &'static dyn DynSolution,
+        benchmark: BenchmarkConfig,
+        input: Input,
+        timeout: Option<Duration>,
     ) -> Self {
         Self {
-            puzzle: id,
-            input_file: input_file.as_ref().to_owned(),
-            p1,
-            p2,
+            puzzle: solution.id(),
+            input,
+            benchmark,
+            timeout,
+            solution,
+            p1: measured(solution, 1, benchmark, None),
+            p2: measured(solution, 2, benchmark, None),
         }
     }
 
+    /// Reports every measured round of the parts to `progress`, together with
+    /// the puzzle and the part it belongs to.
+    pub fn with_progress(
+        mut self,
+        progress: impl Fn(u32, u32, usize, Duration) + Send + Sync + 'static,
+    ) -> Self {
+        let progress = Arc::new(progress);
+        let puzzle = self.puzzle;
+
+        let part_progress = |part| -> Progress {
+            let progress = progress.clone();
+            Arc::new(move |round, duration| progress(puzzle, part, round, duration))
+        };
+
+        self.p1 = measured(self.solution, 1, self.benchmark, Some(part_progress(1)));
+        self.p2 = measured(self.solution, 2, self.benchmark, Some(part_progress(2)));
+        self
+    }
+
+    pub fn id(&self) -> u32 {
+        self.puzzle
+    }
+
     pub fn run(
         &self,
         parts: [bool; 2],
-        mut visitor: impl FnMut(u32, u32, RuntimeStats, String) -> Result<(), AocError>,
+        examples: bool,
+        mut visitor: impl FnMut(u32, u32, Source, Outcome) -> Result<(), AocError>,
     ) -> Result<(), AocError> {
-        let input = std::fs::read_to_string(&self.input_file)?;
+        if examples {
+            return self.run_examples(parts, visitor);
+        }
+
+        for (name, input) in self.read_inputs()? {
+            let input: Arc<str> = input.into();
 
-        if parts[0] {
-            let (stats, result) = (*self.p1)(input.as_str());
-            visitor(self.puzzle, 1, stats, result)?;
+            if parts[0] {
+                let outcome = self.guarded(1, self.p1.clone(), input.clone());
+                let source = Source::Input { name: name.clone() };
+                visitor(self.puzzle, 1, source, outcome)?;
+            }
+
+            if parts[1] {
+                let outcome = self.guarded(2, self.p2.clone(), input.clone());
+                let source = Source::Input { name: name.clone() };
+                visitor(self.puzzle, 2, source, outcome)?;
+            }
         }
 
-        if parts[1] {
-            let (stats, result) = (*self.p2)(input.as_str());
-            visitor(self.puzzle, 2, stats, result)?;
+        Ok(())
+    }
+
+    /// Runs a measured part, catching panics. With a timeout, the part runs
+    /// on a thread of its own, which is abandoned once the timeout expires:
+    /// it stops before its next round, but the round in progress keeps
+    /// running in the background.
+    /// Every round runs inside a `puzzle` span, so the profile attributes the
+    /// traced phases to the part they belong to.
+    fn guarded(&self, part: u32, measured: Measured, input: Arc<str>) -> Outcome {
+        let snippet_input = input.clone();
+        let day = self.puzzle;
+        let run = move || {
+            let _span = tracing::info_span!("puzzle", day, part).entered();
+            panic::catch_unwind(AssertUnwindSafe(|| measured(&input)))
+        };
+
+        let result = match self.timeout {
+            Some(timeout) => {
+                let (sender, receiver) = mpsc::channel();
+                let abandonment = Abandonment::default();
+                let part = abandonment.clone();
+                thread::spawn(move || {
+                    // Nobody is listening any more if the part timed out
+                    let _ = sender.send(part.run(run));
+                });
+
+                match receiver.recv_timeout(timeout) {
+                    Ok(result) => result,
+                    Err(_) => {
+                        abandonment.abandon();
+                        return Outcome::TimedOut { timeout };
+                    }
+                }
+            }
+            None => run(),
+        };
+
+        match result {
+            Ok(Ok((timings, answer))) => Outcome::Solved { timings, answer },
+            Ok(Err(error)) => {
+                let snippet = match &error {
+                    SolveError::Parse { line, column, .. } => {
+                        snippet(&snippet_input, *line, *column)
+                    }
+                    SolveError::Unsolvable(_) => None,
+                };
+
+                Outcome::Failed { error, snippet }
+            }
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+
+                Outcome::Panicked { message }
+            }
+        }
+    }
+
+    /// Reads the input and, when reading from a directory, every named
+    /// alternate input `day-XX.<name>.txt` next to it.
+    fn read_inputs(&self) -> Result<Vec<(Option<String>, String)>, AocError> {
+        let directory = match &self.input {
+            Input::Directory(directory) => directory,
+            Input::File(path) => return Ok(vec![(None, self.read_input(path)?)]),
+            Input::Stdin => return Ok(vec![(None, std::io::read_to_string(std::io::stdin())?)]),
+        };
+
+        let prefix = format!("day-{:02}", self.puzzle);
+        let mut inputs = vec![(
+            None,
+            self.read_input(&directory.join(format!("{prefix}.txt")))?,
+        )];
+
+        let mut alternates = Vec::new();
+        for entry in std::fs::read_dir(directory)? {
+            let path = entry?.path();
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(&prefix))
+                .and_then(|name| name.strip_prefix('.'))
+                .and_then(|name| name.strip_suffix(".txt"));
+
+            if let Some(name) = name {
+                alternates.push((name.to_string(), path.clone()));
+            }
+        }
+
+        alternates.sort();
+        for (name, path) in alternates {
+            inputs.push((Some(name), self.read_input(&path)?));
+        }
+
+        Ok(inputs)
+    }
+
+    fn read_input(&self, path: &Path) -> Result<String, AocError> {
+        std::fs::read_to_string(path).map_err(|error| match error.kind() {
+            std::io::ErrorKind::NotFound => AocError::MissingInput {
+                puzzle: self.puzzle,
+                path: path.to_path_buf(),
+            },
+            _ => error.into(),
+        })
+    }
+
+    fn run_examples(
+        &self,
+        parts: [bool; 2],
+        mut visitor: impl FnMut(u32, u32, Source, Outcome) -> Result<(), AocError>,
+    ) -> Result<(), AocError> {
+        let examples = self.solution.examples();
+
+        for part in [1, 2] {
+            if !parts[part as usize - 1] {
+                continue;
+            }
+
+            for (index, example) in examples
+                .iter()
+                .filter(|example| example.part == part)
+                .enumerate()
+            {
+                let solve = example.solve.clone();
+                let measured = measure(move |input| solve(input), self.benchmark, None);
+                let outcome = self.guarded(part, measured.into(), example.input.into());
+                let source = Source::Example {
+                    index: index + 1,
+                    parameters: example.parameters,
+                    expected: example.expected.clone(),
+                };
+                visitor(self.puzzle, part, source, outcome)?;
+            }
         }
 
         Ok(())
     }
 }
+
+fn measured(
+    solution: &'static dyn DynSolution,
+    part: u32,
+    benchmark: BenchmarkConfig,
+    progress: Option<Progress>,
+) -> Measured {
+    match (solution.phases(part), part) {
+        (Some(phases), _) => measure_phases(phases, benchmark, progress).into(),
+        (None, 1) => measure(move |input| solution.part1(input), benchmark, progress).into(),
+        (None, _) => measure(move |input| solution.part2(input), benchmark, progress).into(),
+    }
+}
+
+/// What came out of running a part.
+#[allow(clippy::large_enum_variant)]
+pub enum Outcome {
+    Solved {
+        timings: Timings,
+        answer: Answer,
+    },
+    /// The part returned an error, with the offending input line for parse
+    /// errors
+    Failed {
+        error: SolveError,
+        snippet: Option<String>,
+    },
+    Panicked {
+        message: String,
+    },
+    TimedOut {
+        timeout: Duration,
+    },
+}
+
+/// Renders a line of the input with a caret under the given column.
+fn snippet(input: &str, line: u32, column: usize) -> Option<String> {
+    let text = input.lines().nth((line as usize).checked_sub(1)?)?;
+    let number = line.to_string();
+    let margin = " ".repeat(number.len());
+    let offset = " ".repeat(column.saturating_sub(1));
+
+    Some(format!(
+        "{margin} |\n{number} | {text}\n{margin} | {offset}^"
+    ))
+}
+
+/// Where a puzzle reads its input from.
+pub enum Input {
+    /// The `day-XX.txt` files in a directory, together with alternate inputs
+    Directory(PathBuf),
+    File(PathBuf),
+    Stdin,
+}
+
+impl From<PathBuf> for Input {
+    fn from(value: PathBuf) -> Self {
+        if value.as_os_str() == "-" {
+            Self::Stdin
+        } else {
+            Self::File(value)
+        }
+    }
+}
+
+/// Where the input of a run comes from.
+pub enum Source {
+    /// The puzzle input, or one of its named alternates
+    Input { name: Option<String> },
+    Example {
+        index: usize,
+        parameters: &'static [(&'static str, i64)],
+        expected: Answer,
+    },
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Input { name: None } => Ok(()),
+            Self::Input { name: Some(name) } => write!(f, " input {name}"),
+            Self::Example {
+                index, parameters, ..
+            } => {
+                write!(f, " example {index}")?;
+
+                if !parameters.is_empty() {
+                    let parameters = parameters
+                        .iter()
+                        .map(|(name, value)| format!("{name}={value}"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    write!(f, " [{parameters}]")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+
+    use super::*;
+
+    fn puzzle(input: Input, timeout: Option<Duration>) -> Puzzle {
+        let solution = registry::solutions()
+            .find(|solution| solution.id() == 1)
+            .unwrap();
+        Puzzle::new(solution, BenchmarkConfig::default(), input, timeout)
+    }
+
+    #[test]
+    fn test_snippet() {
+        let input = "467..114..\n...*......\n";
+
+        assert_eq!(
+            snippet(input, 2, 4).as_deref(),
+            Some("  |\n2 | ...*......\n  |    ^")
+        );
+        assert_eq!(snippet(input, 0, 1), None);
+        assert_eq!(snippet(input, 3, 1), None);
+    }
+
+    #[test]
+    fn test_read_inputs() {
+        let directory = std::env::temp_dir().join(format!("aoc2023-puzzle-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        for (file, contents) in [
+            ("day-01.txt", "main"),
+            ("day-01.small.txt", "small"),
+            ("day-01.big.txt", "big"),
+            ("day-011.txt", "other day"),
+            ("day-02.txt", "other day"),
+        ] {
+            std::fs::write(directory.join(file), contents).unwrap();
+        }
+
+        let inputs = puzzle(Input::Directory(directory.clone()), None).read_inputs();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            inputs.unwrap(),
+            [
+                (None, "main".to_string()),
+                (Some("big".to_string()), "big".to_string()),
+                (Some("small".to_string()), "small".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_guarded_panic() {
+        let measured: Measured = Arc::new(|_: &str| -> Result<(Timings, Answer), SolveError> {
+            panic!("no solution in sight")
+        });
+
+        let outcome = puzzle(Input::Stdin, None).guarded(1, measured, "".into());
+
+        let Outcome::Panicked { message } = outcome else {
+            panic!("the part should have panicked");
+        };
+        assert_eq!(message, "no solution in sight");
+    }
+
+    #[test]
+    fn test_guarded_timeout() {
+        let measured: Measured = Arc::new(|_: &str| -> Result<(Timings, Answer), SolveError> {
+            thread::sleep(Duration::from_millis(500));
+            Err(SolveError::unsolvable("too slow"))
+        });
+        let timeout = Duration::from_millis(10);
+
+        let outcome = puzzle(Input::Stdin, Some(timeout)).guarded(1, measured, "".into());
+
+        assert!(matches!(outcome, Outcome::TimedOut { timeout: t } if t == timeout));
+    }
+}
@@ -0,0 +1,15 @@
+use aoc_util::solution::DynSolution;
+use itertools::Itertools;
+
+include!(concat!(env!("OUT_DIR"), "/solutions.rs"));
+
+/// Id of the template crate, which is registered but skipped when running all
+/// puzzles.
+pub const TEMPLATE_ID: u32 = 0;
+
+pub fn solutions() -> impl Iterator<Item = &'static dyn DynSolution> {
+    SOLUTIONS
+        .iter()
+        .copied()
+        .sorted_by_key(|solution| solution.id())
+}
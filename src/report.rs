@@ -1,36 +1,291 @@
-use std::path::Path;
+use std::{io::Write, path::Path, time::Duration};
 
+use aoc_util::answer::Answer;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
-use crate::{benchmark::RuntimeStats, AocError};
+use crate::{
+    benchmark::{DurationFormatter, Timings},
+    memory::{ByteFormatter, MemoryStats},
+    AocError,
+};
+
+const HEADER: [&str; 18] = [
+    "Day",
+    "Part",
+    "Status",
+    "Answer",
+    "Median (ms)",
+    "Mean (ms)",
+    "Std dev (ms)",
+    "Min (ms)",
+    "Max (ms)",
+    "P90 (ms)",
+    "P99 (ms)",
+    "Parse (ms)",
+    "Solve (ms)",
+    "Peak (bytes)",
+    "Allocated (bytes)",
+    "Allocations",
+    "Outliers",
+    "Runs (ms)",
+];
+
+/// Index of the first summary column in CSV records.
+const SUMMARY_COLUMN: usize = 4;
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+pub enum Format {
+    #[default]
+    Csv,
+    Json,
+    Markdown,
+}
+
+/// Whether a part produced an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Solved,
+    Failed,
+    Panicked,
+    TimedOut,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Solved => write!(f, "OK"),
+            Self::Failed => write!(f, "ERROR"),
+            Self::Panicked => write!(f, "PANIC"),
+            Self::TimedOut => write!(f, "TIMEOUT"),
+        }
+    }
+}
+
+impl std::str::FromStr for Status {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "OK" => Ok(Self::Solved),
+            "ERROR" => Ok(Self::Failed),
+            "PANIC" => Ok(Self::Panicked),
+            "TIMEOUT" => Ok(Self::TimedOut),
+            _ => Err(AocError::InvalidReport(format!("Unknown status {s:?}"))),
+        }
+    }
+}
 
 #[derive(Default)]
 pub struct Report {
-    entries: Vec<(u32, u32, Vec<f64>)>,
+    entries: Vec<Entry>,
+}
+
+/// Benchmark results of a single part, in milliseconds. Parts that did not
+/// produce an answer have no answer and no timings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub puzzle: u32,
+    pub part: u32,
+    pub status: Status,
+    pub answer: Answer,
+    /// Median, mean, standard deviation, min, max, p90 and p99
+    pub summary: [f64; 7],
+    /// Median parse and solve time, for parts split into phases
+    pub phases: Option<(f64, f64)>,
+    /// Heap usage, when counting allocations
+    pub memory: Option<MemoryStats>,
+    pub outliers: usize,
+    pub runs: Vec<f64>,
+}
+
+impl Entry {
+    pub fn median(&self) -> f64 {
+        self.summary[0]
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonReport {
+    entries: Vec<JsonEntry>,
+    sum_of_medians_ms: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonEntry {
+    day: u32,
+    part: u32,
+    status: String,
+    answer: serde_json::Value,
+    median_ms: Option<f64>,
+    mean_ms: Option<f64>,
+    std_dev_ms: Option<f64>,
+    min_ms: Option<f64>,
+    max_ms: Option<f64>,
+    p90_ms: Option<f64>,
+    p99_ms: Option<f64>,
+    parse_ms: Option<f64>,
+    solve_ms: Option<f64>,
+    peak_bytes: Option<usize>,
+    allocated_bytes: Option<usize>,
+    allocations: Option<usize>,
+    outliers: usize,
+    runs_ms: Vec<f64>,
 }
 
 impl Report {
-    pub fn push_entry(&mut self, puzzle: u32, part: u32, stats: &RuntimeStats) {
-        self.entries.push((
+    /// Reads a CSV or JSON report back. Markdown reports are meant for
+    /// reading and cannot be loaded.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AocError> {
+        let contents = std::fs::read_to_string(path)?;
+
+        match contents.trim_start().chars().next() {
+            Some('{') => Self::load_json(&contents),
+            Some('|') => Err(AocError::UnreadableReport { format: "Markdown" }),
+            _ => Self::load_csv(&contents),
+        }
+    }
+
+    fn load_csv(contents: &str) -> Result<Self, AocError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(contents.as_bytes());
+
+        let mut entries = Vec::new();
+        for record in reader.records() {
+            entries.push(parse_entry(&record?)?);
+        }
+
+        Ok(Self { entries })
+    }
+
+    fn load_json(contents: &str) -> Result<Self, AocError> {
+        let report: JsonReport = serde_json::from_str(contents)
+            .map_err(|err| AocError::InvalidReport(err.to_string()))?;
+
+        let entries = report
+            .entries
+            .into_iter()
+            .map(parse_json_entry)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { entries })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter()
+    }
+
+    pub fn entry(&self, puzzle: u32, part: u32) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.puzzle == puzzle && entry.part == part)
+    }
+
+    pub fn push_entry(&mut self, puzzle: u32, part: u32, timings: &Timings, answer: &Answer) {
+        let stats = &timings.total;
+
+        self.entries.push(Entry {
             puzzle,
             part,
-            stats
-                .runs()
-                .iter()
-                .map(|run| run.as_nanos() as f64 / 1_000_000.0)
-                .collect_vec(),
-        ));
+            status: Status::Solved,
+            answer: answer.clone(),
+            summary: [
+                stats.median(),
+                stats.mean(),
+                stats.std_dev(),
+                stats.min(),
+                stats.max(),
+                stats.p90(),
+                stats.p99(),
+            ]
+            .map(millis),
+            phases: timings
+                .phases
+                .as_ref()
+                .map(|(parse, solve)| (millis(parse.median()), millis(solve.median()))),
+            memory: timings.memory,
+            outliers: stats.outliers(),
+            runs: stats.runs().iter().copied().map(millis).collect_vec(),
+        });
     }
 
-    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), AocError> {
-        let mut writer = csv::Writer::from_path(path)?;
+    pub fn push_failure(&mut self, puzzle: u32, part: u32, status: Status) {
+        self.entries.push(Entry {
+            puzzle,
+            part,
+            status,
+            answer: Answer::Unit,
+            summary: [0.0; 7],
+            phases: None,
+            memory: None,
+            outliers: 0,
+            runs: Vec::new(),
+        });
+    }
 
-        for (puzzle, part, values) in self.entries.iter() {
-            let mut record = csv::StringRecord::with_capacity(1024, values.len() + 2);
-            record.push_field(format!("Day {puzzle}").as_str());
-            record.push_field(format!("Part {part}").as_str());
+    pub fn save_to(&self, path: impl AsRef<Path>, format: Format) -> Result<(), AocError> {
+        match format {
+            Format::Csv => self.save_csv(path),
+            Format::Json => self.save_json(path),
+            Format::Markdown => self.save_markdown(path),
+        }
+    }
+
+    fn save_csv(&self, path: impl AsRef<Path>) -> Result<(), AocError> {
+        let mut writer = csv::WriterBuilder::new().flexible(true).from_path(path)?;
+
+        writer.write_record(HEADER)?;
+
+        for entry in self.entries.iter() {
+            let mut record =
+                csv::StringRecord::with_capacity(1024, entry.runs.len() + HEADER.len() - 1);
+            record.push_field(format!("Day {}", entry.puzzle).as_str());
+            record.push_field(format!("Part {}", entry.part).as_str());
+            record.push_field(format!("{}", entry.status).as_str());
+
+            if entry.status != Status::Solved {
+                for _ in 3..HEADER.len() - 1 {
+                    record.push_field("");
+                }
 
-            for value in values.iter() {
+                writer.write_record(&record)?;
+                continue;
+            }
+
+            record.push_field(entry.answer.to_string().as_str());
+
+            for value in entry.summary.iter() {
+                record.push_field(format!("{value}").as_str());
+            }
+
+            match entry.phases {
+                Some((parse, solve)) => {
+                    record.push_field(format!("{parse}").as_str());
+                    record.push_field(format!("{solve}").as_str());
+                }
+                None => {
+                    record.push_field("");
+                    record.push_field("");
+                }
+            }
+
+            match entry.memory {
+                Some(memory) => {
+                    record.push_field(format!("{}", memory.peak).as_str());
+                    record.push_field(format!("{}", memory.allocated).as_str());
+                    record.push_field(format!("{}", memory.allocations).as_str());
+                }
+                None => {
+                    record.push_field("");
+                    record.push_field("");
+                    record.push_field("");
+                }
+            }
+
+            record.push_field(format!("{}", entry.outliers).as_str());
+
+            for value in entry.runs.iter() {
                 record.push_field(format!("{value}").as_str());
             }
 
@@ -39,4 +294,394 @@ impl Report {
 
         Ok(())
     }
+
+    fn save_json(&self, path: impl AsRef<Path>) -> Result<(), AocError> {
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let solved = entry.status == Status::Solved;
+                let [median, mean, std_dev, min, max, p90, p99] =
+                    entry.summary.map(|value| solved.then_some(value));
+                JsonEntry {
+                    day: entry.puzzle,
+                    part: entry.part,
+                    status: entry.status.to_string(),
+                    answer: match &entry.answer {
+                        Answer::Integer(value) => (*value).into(),
+                        Answer::BigInteger(value) => value.to_string().into(),
+                        Answer::String(value) => value.as_str().into(),
+                        Answer::Unit => serde_json::Value::Null,
+                    },
+                    median_ms: median,
+                    mean_ms: mean,
+                    std_dev_ms: std_dev,
+                    min_ms: min,
+                    max_ms: max,
+                    p90_ms: p90,
+                    p99_ms: p99,
+                    parse_ms: entry.phases.map(|(parse, _)| parse),
+                    solve_ms: entry.phases.map(|(_, solve)| solve),
+                    peak_bytes: entry.memory.map(|memory| memory.peak),
+                    allocated_bytes: entry.memory.map(|memory| memory.allocated),
+                    allocations: entry.memory.map(|memory| memory.allocations),
+                    outliers: entry.outliers,
+                    runs_ms: entry.runs.clone(),
+                }
+            })
+            .collect_vec();
+
+        let report = JsonReport {
+            entries,
+            sum_of_medians_ms: self.sum_of_medians(),
+        };
+
+        let writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer_pretty(writer, &report)?;
+
+        Ok(())
+    }
+
+    fn save_markdown(&self, path: impl AsRef<Path>) -> Result<(), AocError> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let benchmarked = self.entries.iter().any(|entry| entry.runs.len() > 1);
+        let phased = self.entries.iter().any(|entry| entry.phases.is_some());
+        let counted = self.entries.iter().any(|entry| entry.memory.is_some());
+
+        let mut header = vec!["Day", "Part"];
+        if benchmarked {
+            header.extend(["Median", "Mean ± σ", "Min", "Max"]);
+        } else {
+            header.push("Time");
+        }
+        if phased {
+            header.extend(["Parse", "Solve"]);
+        }
+        if counted {
+            header.extend(["Peak", "Allocated", "Allocations"]);
+        }
+
+        writeln!(writer, "| {} |", header.join(" | "))?;
+        writeln!(writer, "|{}", "---:|".repeat(header.len()))?;
+
+        for entry in self.entries.iter() {
+            let [median, mean, std_dev, min, max, ..] = entry.summary.map(duration);
+
+            let mut row = vec![entry.puzzle.to_string(), entry.part.to_string()];
+
+            if entry.status != Status::Solved {
+                row.push(format!("**{}**", entry.status));
+                row.resize(header.len(), String::new());
+                writeln!(writer, "| {} |", row.join(" | "))?;
+                continue;
+            }
+
+            row.push(DurationFormatter(median).to_string());
+            if benchmarked {
+                row.push(format!(
+                    "{} ± {}",
+                    DurationFormatter(mean),
+                    DurationFormatter(std_dev)
+                ));
+                row.push(DurationFormatter(min).to_string());
+                row.push(DurationFormatter(max).to_string());
+            }
+            if phased {
+                match entry.phases {
+                    Some((parse, solve)) => {
+                        row.push(DurationFormatter(duration(parse)).to_string());
+                        row.push(DurationFormatter(duration(solve)).to_string());
+                    }
+                    None => row.extend([String::new(), String::new()]),
+                }
+            }
+            if counted {
+                match entry.memory {
+                    Some(memory) => {
+                        row.push(ByteFormatter(memory.peak).to_string());
+                        row.push(ByteFormatter(memory.allocated).to_string());
+                        row.push(memory.allocations.to_string());
+                    }
+                    None => row.resize(row.len() + 3, String::new()),
+                }
+            }
+
+            writeln!(writer, "| {} |", row.join(" | "))?;
+        }
+
+        let label = if benchmarked {
+            "Sum of median solve times"
+        } else {
+            "Sum of solve times"
+        };
+        let mut row = vec![
+            String::new(),
+            format!("**{label}**"),
+            format!("**{}**", DurationFormatter(duration(self.sum_of_medians()))),
+        ];
+        row.resize(header.len(), String::new());
+
+        writeln!(writer, "| {} |", row.join(" | "))?;
+
+        Ok(())
+    }
+
+    fn sum_of_medians(&self) -> f64 {
+        self.entries.iter().map(Entry::median).sum()
+    }
+}
+
+fn parse_entry(record: &csv::StringRecord) -> Result<Entry, AocError> {
+    let invalid = || AocError::InvalidReport(format!("Invalid record {record:?}"));
+
+    if record.len() < HEADER.len() - 1 {
+        return Err(invalid());
+    }
+
+    let puzzle = record[0]
+        .strip_prefix("Day ")
+        .and_then(|puzzle| puzzle.parse().ok())
+        .ok_or_else(invalid)?;
+    let part = record[1]
+        .strip_prefix("Part ")
+        .and_then(|part| part.parse().ok())
+        .ok_or_else(invalid)?;
+    let status = record[2].parse()?;
+
+    if status != Status::Solved {
+        return Ok(Entry {
+            puzzle,
+            part,
+            status,
+            answer: Answer::Unit,
+            summary: [0.0; 7],
+            phases: None,
+            memory: None,
+            outliers: 0,
+            runs: Vec::new(),
+        });
+    }
+
+    if record.len() < HEADER.len() {
+        return Err(invalid());
+    }
+
+    let Ok(answer) = record[3].parse();
+
+    let mut summary = [0.0; 7];
+    for (value, field) in summary.iter_mut().zip(record.iter().skip(SUMMARY_COLUMN)) {
+        *value = field.parse().map_err(|_| invalid())?;
+    }
+
+    let phases_column = SUMMARY_COLUMN + summary.len();
+    let phases = match (&record[phases_column], &record[phases_column + 1]) {
+        ("", "") => None,
+        (parse, solve) => Some((
+            parse.parse().map_err(|_| invalid())?,
+            solve.parse().map_err(|_| invalid())?,
+        )),
+    };
+
+    let memory_column = phases_column + 2;
+    let memory = match (
+        &record[memory_column],
+        &record[memory_column + 1],
+        &record[memory_column + 2],
+    ) {
+        ("", "", "") => None,
+        (peak, allocated, allocations) => Some(MemoryStats {
+            peak: peak.parse().map_err(|_| invalid())?,
+            allocated: allocated.parse().map_err(|_| invalid())?,
+            allocations: allocations.parse().map_err(|_| invalid())?,
+        }),
+    };
+
+    let outliers = record[memory_column + 3].parse().map_err(|_| invalid())?;
+    let runs = record
+        .iter()
+        .skip(HEADER.len() - 1)
+        .map(|field| field.parse().map_err(|_| invalid()))
+        .collect::<Result<Vec<f64>, _>>()?;
+
+    Ok(Entry {
+        puzzle,
+        part,
+        status,
+        answer,
+        summary,
+        phases,
+        memory,
+        outliers,
+        runs,
+    })
+}
+
+fn parse_json_entry(entry: JsonEntry) -> Result<Entry, AocError> {
+    let status = entry.status.parse()?;
+    let invalid = || {
+        AocError::InvalidReport(format!(
+            "Day {} part {} has no timings",
+            entry.day, entry.part
+        ))
+    };
+
+    let answer = match &entry.answer {
+        serde_json::Value::Null => Answer::Unit,
+        serde_json::Value::Number(value) => {
+            value.as_i64().map(Answer::Integer).ok_or_else(|| {
+                AocError::InvalidReport(format!("Invalid answer {value} for day {}", entry.day))
+            })?
+        }
+        serde_json::Value::String(value) => {
+            let Ok(answer) = value.parse();
+            answer
+        }
+        value => {
+            return Err(AocError::InvalidReport(format!(
+                "Invalid answer {value} for day {}",
+                entry.day
+            )))
+        }
+    };
+
+    let mut summary = [0.0; 7];
+    if status == Status::Solved {
+        let fields = [
+            entry.median_ms,
+            entry.mean_ms,
+            entry.std_dev_ms,
+            entry.min_ms,
+            entry.max_ms,
+            entry.p90_ms,
+            entry.p99_ms,
+        ];
+        for (value, field) in summary.iter_mut().zip(fields) {
+            *value = field.ok_or_else(invalid)?;
+        }
+    }
+
+    let memory = match (entry.peak_bytes, entry.allocated_bytes, entry.allocations) {
+        (Some(peak), Some(allocated), Some(allocations)) => Some(MemoryStats {
+            peak,
+            allocated,
+            allocations,
+        }),
+        _ => None,
+    };
+
+    Ok(Entry {
+        puzzle: entry.day,
+        part: entry.part,
+        status,
+        answer,
+        summary,
+        phases: entry.parse_ms.zip(entry.solve_ms),
+        memory,
+        outliers: entry.outliers,
+        runs: entry.runs_ms,
+    })
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_nanos() as f64 / 1_000_000.0
+}
+
+fn duration(millis: f64) -> Duration {
+    Duration::from_secs_f64(millis / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Path in the temporary directory, unique to this process and `name`.
+    fn scratch(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("aoc2023-report-{}-{name}", std::process::id()))
+    }
+
+    fn report() -> Report {
+        let mut report = Report {
+            entries: vec![Entry {
+                puzzle: 5,
+                part: 1,
+                status: Status::Solved,
+                answer: Answer::Integer(35),
+                summary: [1.25, 1.5, 0.125, 1.0, 2.5, 2.0, 2.5],
+                phases: Some((0.25, 1.0)),
+                memory: Some(MemoryStats {
+                    peak: 4096,
+                    allocated: 8192,
+                    allocations: 3,
+                }),
+                outliers: 1,
+                runs: vec![1.0, 1.25, 2.5],
+            }],
+        };
+        report.push_failure(5, 2, Status::TimedOut);
+        report
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (format, name) in [
+            (Format::Csv, "round-trip.csv"),
+            (Format::Json, "round-trip.json"),
+        ] {
+            let path = scratch(name);
+            report().save_to(&path, format).unwrap();
+            let loaded = Report::load(&path);
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(loaded.unwrap().entries, report().entries, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_load_markdown() {
+        let path = scratch("baseline.md");
+        report().save_to(&path, Format::Markdown).unwrap();
+        let loaded = Report::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            loaded,
+            Err(AocError::UnreadableReport { format: "Markdown" })
+        ));
+    }
+
+    #[test]
+    fn test_parse_entry() {
+        let header = HEADER.join(",");
+        let solved = "Day 5,Part 1,OK,35,1,1,0,1,1,1,1,,,,,,0,1";
+
+        for (record, valid) in [
+            (solved, true),
+            ("Day 5,Part 2,ERROR,,,,,,,,,,,,,,", true),
+            ("Day 5,Part 1,OK", false),
+            ("Five,Part 1,OK,35,1,1,0,1,1,1,1,,,,,,0,1", false),
+            ("Day 5,Part 1,DONE,35,1,1,0,1,1,1,1,,,,,,0,1", false),
+            ("Day 5,Part 1,OK,35,fast,1,0,1,1,1,1,,,,,,0,1", false),
+            ("Day 5,Part 1,OK,35,1,1,0,1,1,1,1,0.5,,,,,0,1", false),
+        ] {
+            let loaded = Report::load_csv(&format!("{header}\n{record}\n"));
+            assert_eq!(loaded.is_ok(), valid, "{record}");
+        }
+
+        let report = Report::load_csv(&format!("{header}\n{solved}\n")).unwrap();
+        let entry = report.entry(5, 1).unwrap();
+        assert_eq!(entry.answer, Answer::Integer(35));
+        assert_eq!(entry.runs, [1.0]);
+        assert_eq!(entry.phases, None);
+    }
+
+    #[test]
+    fn test_load_json_without_timings() {
+        let json = r#"{"entries":[{"day":5,"part":1,"status":"OK","answer":35,"outliers":0,"runs_ms":[]}],"sum_of_medians_ms":0}"#;
+        assert!(matches!(
+            Report::load_json(json),
+            Err(AocError::InvalidReport(_))
+        ));
+    }
 }
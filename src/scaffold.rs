@@ -0,0 +1,152 @@
+use std::{fs, path::Path};
+
+use crate::AocError;
+
+const TEMPLATE_PACKAGE: &str = "aoc2023-template";
+const TEMPLATE_ID: &str = "const ID: u32 = 0;";
+const TEMPLATE_TITLE: &str = "const TITLE: &'static str = \"Template\";";
+
+/// Creates `crates/day-XX` from the template crate, registers it as a
+/// dependency of the runner and creates an empty input file for it.
+pub fn new_day(root: &Path, inputs: &Path, day: u32, title: Option<&str>) -> Result<(), AocError> {
+    if !(1..=25).contains(&day) {
+        return Err(AocError::NoSuchPuzzle { puzzle: day });
+    }
+
+    let package = format!("aoc2023-{day:02}");
+    let directory = root.join("crates").join(format!("day-{day:02}"));
+
+    if directory.exists() {
+        return Err(AocError::DayExists { path: directory });
+    }
+
+    let template = root.join("crates").join("template");
+    let manifest = fs::read_to_string(template.join("Cargo.toml"))?;
+    let source = fs::read_to_string(template.join("src").join("lib.rs"))?;
+
+    if !manifest.contains(TEMPLATE_PACKAGE)
+        || !source.contains(TEMPLATE_ID)
+        || !source.contains(TEMPLATE_TITLE)
+    {
+        return Err(AocError::InvalidTemplate);
+    }
+
+    let title = title.map_or_else(|| format!("Day {day}"), str::to_string);
+    let manifest = manifest.replace(TEMPLATE_PACKAGE, &package);
+    let source = source
+        .replace(TEMPLATE_ID, &format!("const ID: u32 = {day};"))
+        .replace(
+            TEMPLATE_TITLE,
+            &format!("const TITLE: &'static str = {title:?};"),
+        );
+
+    let root_manifest = root.join("Cargo.toml");
+    let registered = register(&fs::read_to_string(&root_manifest)?, day)?;
+
+    fs::create_dir_all(directory.join("src"))?;
+    fs::write(directory.join("Cargo.toml"), manifest)?;
+    fs::write(directory.join("src").join("lib.rs"), source)?;
+    fs::write(&root_manifest, registered)?;
+    println!("Created {}", directory.display());
+
+    let input = inputs.join(format!("day-{day:02}.txt"));
+    if !input.exists() {
+        fs::create_dir_all(inputs)?;
+        fs::write(&input, "")?;
+        println!("Created {}", input.display());
+    }
+
+    Ok(())
+}
+
+/// Adds the crate of a day to the `[dependencies]` of the root manifest,
+/// next to the other days.
+fn register(manifest: &str, day: u32) -> Result<String, AocError> {
+    let package = format!("aoc2023-{day:02}");
+    let entry = format!("{package} = {{ path = \"crates/day-{day:02}\" }}");
+    let lines = manifest.lines().collect::<Vec<_>>();
+
+    let start = lines
+        .iter()
+        .position(|line| line.trim() == "[dependencies]")
+        .ok_or(AocError::InvalidManifest("no [dependencies] table"))?
+        + 1;
+    let end = lines[start..]
+        .iter()
+        .position(|line| line.starts_with('['))
+        .map_or(lines.len(), |offset| start + offset);
+
+    let key = |line: &str| {
+        line.split('=')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+    let mut days = (start..end).filter(|&index| key(lines[index]).starts_with("aoc2023-"));
+
+    if days.clone().any(|index| key(lines[index]) == package) {
+        return Ok(manifest.to_string());
+    }
+
+    // Days are kept in order, after the template crate
+    let position = days
+        .clone()
+        .find(|&index| key(lines[index]) > package && key(lines[index]) != TEMPLATE_PACKAGE)
+        .or_else(|| days.next_back().map(|index| index + 1))
+        .unwrap_or(end);
+
+    let mut registered = lines;
+    registered.insert(position, entry.as_str());
+
+    let mut registered = registered.join("\n");
+    if manifest.ends_with('\n') {
+        registered.push('\n');
+    }
+
+    Ok(registered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_MANIFEST: &str = r#"[package]
+name = "aoc2023"
+
+[dependencies]
+anyhow.workspace = true
+aoc2023-template = { path = "crates/template" }
+aoc2023-01 = { path = "crates/day-01" }
+aoc2023-03 = { path = "crates/day-03" }
+clap = { version = "4.4", features = ["derive"] }
+
+[build-dependencies]
+toml.workspace = true
+"#;
+
+    #[test]
+    fn test_register_between_days() {
+        let manifest = register(TEST_MANIFEST, 2).unwrap();
+        assert!(manifest.contains(
+            "aoc2023-01 = { path = \"crates/day-01\" }\n\
+             aoc2023-02 = { path = \"crates/day-02\" }\n\
+             aoc2023-03 = { path = \"crates/day-03\" }\n"
+        ));
+    }
+
+    #[test]
+    fn test_register_after_last_day() {
+        let manifest = register(TEST_MANIFEST, 4).unwrap();
+        assert!(manifest.contains(
+            "aoc2023-03 = { path = \"crates/day-03\" }\n\
+             aoc2023-04 = { path = \"crates/day-04\" }\n\
+             clap"
+        ));
+    }
+
+    #[test]
+    fn test_register_twice() {
+        assert_eq!(register(TEST_MANIFEST, 3).unwrap(), TEST_MANIFEST);
+    }
+}
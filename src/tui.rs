@@ -0,0 +1,544 @@
+use std::{
+    panic,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use aoc_util::{answer::Answer, solution::DynSolution};
+use ratatui::{
+    crossterm::event::{self, Event as TerminalEvent, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, Cell, Paragraph, Row, Sparkline, Table, TableState},
+    Frame,
+};
+
+use crate::{
+    answers::{Answers, Verdict},
+    benchmark::{BenchmarkConfig, DurationFormatter, RuntimeStats},
+    puzzle::{Input, Outcome, Puzzle},
+    registry, AocError,
+};
+
+/// Lists every day with its latest answers and timings, running days in the
+/// background on request until the user quits.
+pub fn tui(inputs: &Path, answers: Answers, benchmark: BenchmarkConfig) -> Result<(), AocError> {
+    let mut app = App::new(registry::solutions(), answers, benchmark);
+
+    let (requests, request_receiver) = mpsc::channel();
+    let (event_sender, events) = mpsc::channel();
+    let inputs = inputs.to_path_buf();
+    thread::Builder::new()
+        .name("tui-runner".to_string())
+        .spawn(move || runner(&inputs, benchmark, request_receiver, event_sender))?;
+
+    // Both ratatui and the filter below replace the panic hook, so the one in
+    // place before is put back once the terminal is restored
+    let previous_hook = Arc::new(panic::take_hook());
+    let forward = previous_hook.clone();
+    panic::set_hook(Box::new(move |info| forward(info)));
+    let restore_hook = move || {
+        drop(panic::take_hook());
+        panic::set_hook(Box::new(move |info| previous_hook(info)));
+    };
+
+    let mut terminal = match ratatui::try_init() {
+        Ok(terminal) => terminal,
+        Err(error) => {
+            restore_hook();
+            return Err(error.into());
+        }
+    };
+
+    // Panics of the solutions are reported in the table, printing them would
+    // garble the screen
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if thread::current().name() == Some("main") {
+            hook(info);
+        }
+    }));
+
+    let result = (|| -> Result<(), AocError> {
+        loop {
+            for event in events.try_iter() {
+                app.apply(event);
+            }
+
+            terminal.draw(|frame| app.draw(frame))?;
+
+            if !event::poll(Duration::from_millis(50))? {
+                continue;
+            }
+
+            let TerminalEvent::Key(key) = event::read()? else {
+                continue;
+            };
+
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Up | KeyCode::Char('k') => app.table.select_previous(),
+                KeyCode::Down | KeyCode::Char('j') => app.table.select_next(),
+                KeyCode::Enter | KeyCode::Char('r') => {
+                    if let Some(index) = app.table.selected() {
+                        app.queue(index, &requests);
+                    }
+                }
+                KeyCode::Char('a') => {
+                    for index in 0..app.days.len() {
+                        app.queue(index, &requests);
+                    }
+                }
+                _ => {}
+            }
+        }
+    })();
+
+    ratatui::restore();
+    restore_hook();
+    result
+}
+
+/// Runs the requested days one after another, reporting their progress.
+fn runner(
+    inputs: &Path,
+    benchmark: BenchmarkConfig,
+    requests: Receiver<&'static dyn DynSolution>,
+    events: Sender<Event>,
+) {
+    for solution in requests {
+        let puzzle = solution.id();
+        let _ = events.send(Event::Started { puzzle });
+
+        let input = Input::File(input_path(inputs, puzzle));
+        let progress = events.clone();
+        let runnable = Puzzle::new(solution, benchmark, input, None).with_progress(
+            move |puzzle, part, round, duration| {
+                let _ = progress.send(Event::Round {
+                    puzzle,
+                    part,
+                    round,
+                    duration,
+                });
+            },
+        );
+
+        let result = runnable.run([true, true], false, |puzzle, part, _, outcome| {
+            let _ = events.send(Event::Finished {
+                puzzle,
+                part,
+                result: outcome.into(),
+            });
+            Ok(())
+        });
+
+        let _ = events.send(Event::Done {
+            puzzle,
+            error: result.err().map(|error| error.to_string()),
+        });
+    }
+}
+
+fn input_path(inputs: &Path, puzzle: u32) -> PathBuf {
+    inputs.join(format!("day-{puzzle:02}.txt"))
+}
+
+/// What the runner reports while running a day.
+enum Event {
+    Started {
+        puzzle: u32,
+    },
+    Round {
+        puzzle: u32,
+        part: u32,
+        round: usize,
+        duration: Duration,
+    },
+    Finished {
+        puzzle: u32,
+        part: u32,
+        result: PartResult,
+    },
+    /// The day has finished running, with the error that stopped it early
+    Done {
+        puzzle: u32,
+        error: Option<String>,
+    },
+}
+
+/// Latest result of a part, stripped down to what the table shows.
+enum PartResult {
+    Solved { answer: Answer, stats: RuntimeStats },
+    Failed(String),
+}
+
+impl From<Outcome> for PartResult {
+    fn from(outcome: Outcome) -> Self {
+        match outcome {
+            Outcome::Solved { timings, answer } => Self::Solved {
+                answer,
+                stats: timings.total,
+            },
+            Outcome::Failed { error, .. } => Self::Failed(format!("ERROR: {error}")),
+            Outcome::Panicked { message } => Self::Failed(format!("PANIC: {message}")),
+            Outcome::TimedOut { timeout } => {
+                Self::Failed(format!("TIMEOUT after {}", DurationFormatter(timeout)))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DayState {
+    Idle,
+    Queued,
+    Running,
+}
+
+#[derive(Default)]
+struct PartState {
+    result: Option<PartResult>,
+    /// Durations of the measured rounds of the latest run, filled in as they
+    /// complete
+    runs: Vec<Duration>,
+    running: bool,
+}
+
+struct DayEntry {
+    solution: &'static dyn DynSolution,
+    state: DayState,
+    error: Option<String>,
+    parts: [PartState; 2],
+}
+
+struct App {
+    days: Vec<DayEntry>,
+    answers: Answers,
+    benchmark: BenchmarkConfig,
+    table: TableState,
+}
+
+impl App {
+    fn new(
+        solutions: impl Iterator<Item = &'static dyn DynSolution>,
+        answers: Answers,
+        benchmark: BenchmarkConfig,
+    ) -> Self {
+        let days = solutions
+            .filter(|solution| solution.id() != registry::TEMPLATE_ID)
+            .map(|solution| DayEntry {
+                solution,
+                state: DayState::Idle,
+                error: None,
+                parts: Default::default(),
+            })
+            .collect();
+
+        Self {
+            days,
+            answers,
+            benchmark,
+            table: TableState::default().with_selected(0),
+        }
+    }
+
+    /// Sends the day at `index` to the runner, unless it is already waiting
+    /// or running.
+    fn queue(&mut self, index: usize, requests: &Sender<&'static dyn DynSolution>) {
+        let day = &mut self.days[index];
+
+        if day.state == DayState::Idle && requests.send(day.solution).is_ok() {
+            day.state = DayState::Queued;
+        }
+    }
+
+    fn day_mut(&mut self, puzzle: u32) -> Option<&mut DayEntry> {
+        self.days.iter_mut().find(|day| day.solution.id() == puzzle)
+    }
+
+    fn apply(&mut self, event: Event) {
+        match event {
+            Event::Started { puzzle } => {
+                if let Some(day) = self.day_mut(puzzle) {
+                    day.state = DayState::Running;
+                    day.error = None;
+                    for part in &mut day.parts {
+                        part.runs.clear();
+                        part.running = false;
+                    }
+                    day.parts[0].running = true;
+                }
+            }
+            Event::Round {
+                puzzle,
+                part,
+                round,
+                duration,
+            } => {
+                if let Some(day) = self.day_mut(puzzle) {
+                    let part = &mut day.parts[part as usize - 1];
+                    part.runs.truncate(round - 1);
+                    part.runs.push(duration);
+                }
+            }
+            Event::Finished {
+                puzzle,
+                part,
+                result,
+            } => {
+                if let Some(day) = self.day_mut(puzzle) {
+                    day.parts[part as usize - 1].result = Some(result);
+                    day.parts[part as usize - 1].running = false;
+                    if let Some(next) = day.parts.get_mut(part as usize) {
+                        next.running = true;
+                    }
+                }
+            }
+            Event::Done { puzzle, error } => {
+                if let Some(day) = self.day_mut(puzzle) {
+                    day.state = DayState::Idle;
+                    day.error = error;
+                    for part in &mut day.parts {
+                        part.running = false;
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [table_area, details_area, help_area] = Layout::vertical([
+            Constraint::Min(5),
+            Constraint::Length(10),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        self.draw_table(frame, table_area);
+
+        if let Some(day) = self.table.selected().and_then(|index| self.days.get(index)) {
+            self.draw_details(frame, details_area, day);
+        }
+
+        frame.render_widget(
+            Line::from("↑/↓ select · enter run day · a run all days · q quit").dark_gray(),
+            help_area,
+        );
+    }
+
+    fn draw_table(&mut self, frame: &mut Frame, area: Rect) {
+        let header = Row::new(["Day", "Title", "Part 1", "Median", "Part 2", "Median"])
+            .style(Style::new().add_modifier(Modifier::BOLD));
+
+        let rows = self
+            .days
+            .iter()
+            .map(|day| {
+                let puzzle = day.solution.id();
+                let mut cells = vec![
+                    Cell::from(format!("{puzzle:02}")),
+                    Cell::from(day.solution.title()),
+                ];
+
+                for (index, part) in day.parts.iter().enumerate() {
+                    let (answer, time) = self.part_cells(day, index as u32 + 1, part);
+                    cells.push(answer);
+                    cells.push(time);
+                }
+
+                Row::new(cells)
+            })
+            .collect::<Vec<_>>();
+
+        let title_width = self
+            .days
+            .iter()
+            .map(|day| day.solution.title().chars().count())
+            .chain(["Title".len()])
+            .max()
+            .unwrap_or_default();
+
+        let widths = [
+            Constraint::Length(3),
+            Constraint::Length(title_width as u16),
+            Constraint::Fill(1),
+            Constraint::Length(12),
+            Constraint::Fill(1),
+            Constraint::Length(12),
+        ];
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::bordered().title(" Advent of Code 2023 "))
+            .row_highlight_style(Style::new().reversed());
+
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+
+    fn part_cells(
+        &self,
+        day: &DayEntry,
+        part: u32,
+        state: &PartState,
+    ) -> (Cell<'static>, Cell<'static>) {
+        let answer = match (&state.result, &day.error) {
+            (Some(PartResult::Solved { answer, .. }), _) => {
+                let color = match self.answers.verify(day.solution.id(), part, answer) {
+                    Verdict::Pass => Color::Green,
+                    Verdict::Fail { .. } => Color::Red,
+                    Verdict::Unknown => Color::Reset,
+                };
+                Cell::from(answer.to_string()).fg(color)
+            }
+            (Some(PartResult::Failed(message)), _) => Cell::from(message.clone()).red(),
+            (None, Some(error)) => Cell::from(error.clone()).red(),
+            (None, None) => Cell::from(""),
+        };
+
+        let time = if state.running {
+            Cell::from(self.round_progress(state.runs.len())).yellow()
+        } else if day.state == DayState::Queued {
+            Cell::from("queued").dark_gray()
+        } else if let Some(PartResult::Solved { stats, .. }) = &state.result {
+            Cell::from(DurationFormatter(stats.median()).to_string())
+        } else {
+            Cell::from("")
+        };
+
+        (answer, time)
+    }
+
+    fn round_progress(&self, rounds: usize) -> String {
+        match self.benchmark.budget {
+            Some(_) => format!("round {}", rounds + 1),
+            None => format!("round {}/{}", rounds + 1, self.benchmark.rounds),
+        }
+    }
+
+    fn draw_details(&self, frame: &mut Frame, area: Rect, day: &DayEntry) {
+        let block = Block::bordered().title(format!(
+            " Day {:02}: {} ",
+            day.solution.id(),
+            day.solution.title()
+        ));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let columns = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).split(inner);
+
+        for (index, (part, area)) in day.parts.iter().zip(columns.iter()).enumerate() {
+            let [title_area, sparkline_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).areas(*area);
+
+            frame.render_widget(
+                Paragraph::new(self.part_summary(day, index as u32 + 1, part)),
+                title_area,
+            );
+
+            // The latest rounds that fit
+            let skip = part
+                .runs
+                .len()
+                .saturating_sub(sparkline_area.width as usize);
+            let data = part.runs[skip..]
+                .iter()
+                .map(|duration| duration.as_nanos() as u64)
+                .collect::<Vec<_>>();
+
+            frame.render_widget(Sparkline::default().data(&data).cyan(), sparkline_area);
+        }
+    }
+
+    fn part_summary(&self, day: &DayEntry, part: u32, state: &PartState) -> String {
+        let mut summary = format!("Part {part}: {} rounds", state.runs.len());
+
+        if let Some(PartResult::Solved { answer, stats }) = &state.result {
+            if !state.running {
+                summary += &format!(
+                    ", min {}, median {}, max {}",
+                    DurationFormatter(stats.min()),
+                    DurationFormatter(stats.median()),
+                    DurationFormatter(stats.max()),
+                );
+            }
+
+            if let Verdict::Fail { expected } = self.answers.verify(day.solution.id(), part, answer)
+            {
+                summary += &format!(", expected {expected}");
+            }
+        }
+
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> App {
+        let benchmark = BenchmarkConfig {
+            rounds: 3,
+            ..Default::default()
+        };
+        App::new(registry::solutions(), Answers::default(), benchmark)
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut app = app();
+        let puzzle = app.days[0].solution.id();
+
+        app.apply(Event::Started { puzzle });
+        for round in 1..=2 {
+            app.apply(Event::Round {
+                puzzle,
+                part: 1,
+                round,
+                duration: Duration::from_millis(round as u64),
+            });
+        }
+
+        assert_eq!(app.days[0].state, DayState::Running);
+        assert!(app.days[0].parts[0].running);
+        assert_eq!(app.days[0].parts[0].runs.len(), 2);
+        assert_eq!(app.round_progress(2), "round 3/3");
+
+        app.apply(Event::Finished {
+            puzzle,
+            part: 1,
+            result: PartResult::Failed("PANIC: oops".to_string()),
+        });
+        assert!(!app.days[0].parts[0].running);
+        assert!(app.days[0].parts[1].running);
+
+        app.apply(Event::Done {
+            puzzle,
+            error: None,
+        });
+        assert_eq!(app.days[0].state, DayState::Idle);
+        assert!(!app.days[0].parts[1].running);
+    }
+
+    #[test]
+    fn test_queue() {
+        let mut app = app();
+        let (requests, receiver) = mpsc::channel();
+
+        app.queue(0, &requests);
+        app.queue(0, &requests);
+
+        assert_eq!(app.days[0].state, DayState::Queued);
+        assert_eq!(receiver.try_iter().count(), 1);
+    }
+}
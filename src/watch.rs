@@ -0,0 +1,210 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    benchmark::DurationFormatter,
+    report::{Entry, Report, Status},
+    AocError,
+};
+
+/// Polls the sources and the input of a day, re-running its tests, its
+/// examples and its real input whenever one of them changes.
+pub fn watch(root: &Path, inputs: &Path, day: u32, interval: Duration) -> Result<(), AocError> {
+    let sources = root
+        .join("crates")
+        .join(format!("day-{day:02}"))
+        .join("src");
+    if !sources.is_dir() {
+        return Err(AocError::NoSuchPuzzle { puzzle: day });
+    }
+
+    // The runs below start in the workspace root
+    let inputs = std::path::absolute(inputs)?;
+    let input = inputs.join(format!("day-{day:02}.txt"));
+    let report = std::env::temp_dir().join(format!("aoc2023-watch-{day:02}.csv"));
+
+    let mut snapshot = BTreeMap::new();
+    let mut previous = None;
+
+    loop {
+        let current = modification_times(&[sources.as_path(), input.as_path()])?;
+
+        if current != snapshot {
+            snapshot = current;
+            previous = run_cycle(root, &inputs, day, &report, previous)?;
+            println!("Watching {} and {}", sources.display(), input.display());
+        }
+
+        std::thread::sleep(interval);
+    }
+}
+
+/// Runs the tests, the examples and the input of a day, then prints how the
+/// answers and timings differ from the previous cycle.
+fn run_cycle(
+    root: &Path,
+    inputs: &Path,
+    day: u32,
+    report: &Path,
+    previous: Option<Report>,
+) -> Result<Option<Report>, AocError> {
+    let day_arg = day.to_string();
+
+    let tests = cargo(root)
+        .args(["test", "-q", "-p", &format!("aoc2023-{day:02}")])
+        .status()?;
+    println!(
+        "Tests {}",
+        if tests.success() { "passed" } else { "failed" }
+    );
+
+    cargo(root)
+        .args(["run", "-q", "--", &day_arg, "--examples"])
+        .status()?;
+
+    let _ = std::fs::remove_file(report);
+    cargo(root)
+        .args(["run", "-q", "--", &day_arg, "--out"])
+        .arg(report)
+        .arg("--inputs-dir")
+        .arg(inputs)
+        .status()?;
+
+    // A failed build or a missing input leaves no report behind
+    let Ok(current) = Report::load(report) else {
+        return Ok(previous);
+    };
+
+    if let Some(previous) = previous.as_ref() {
+        println!("Compared to the previous run:");
+        for entry in current.entries() {
+            println!(
+                "{}",
+                describe_change(previous.entry(entry.puzzle, entry.part), entry)
+            );
+        }
+    }
+
+    Ok(Some(current))
+}
+
+fn cargo(root: &Path) -> Command {
+    let mut command = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    command.current_dir(root);
+
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+
+    command
+}
+
+fn describe_change(previous: Option<&Entry>, current: &Entry) -> String {
+    let label = format!("Day {:02} part {}", current.puzzle, current.part);
+
+    let (previous, current) = match (previous, current.status) {
+        (_, Status::Panicked | Status::TimedOut | Status::Failed) => {
+            return format!("{label}: {}", current.status)
+        }
+        (Some(previous), _) if previous.status == Status::Solved => (previous, current),
+        _ => return format!("{label}: {} (new)", current.answer),
+    };
+
+    let answer = if previous.answer == current.answer {
+        format!("{} (unchanged)", current.answer)
+    } else {
+        format!("{} (was {})", current.answer, previous.answer)
+    };
+
+    let timings = format!(
+        "{} -> {}",
+        DurationFormatter(Duration::from_secs_f64(previous.median() / 1000.0)),
+        DurationFormatter(Duration::from_secs_f64(current.median() / 1000.0)),
+    );
+
+    // Without a time to compare to there is no relative change
+    if previous.median() <= 0.0 {
+        return format!("{label}: {answer}, {timings}");
+    }
+
+    let change = (current.median() - previous.median()) / previous.median() * 100.0;
+    format!("{label}: {answer}, {timings} ({change:+.1}%)")
+}
+
+/// Modification times of the given files, and of every file below the given
+/// directories. Missing paths are skipped.
+fn modification_times(paths: &[&Path]) -> Result<BTreeMap<PathBuf, SystemTime>, AocError> {
+    let mut times = BTreeMap::new();
+    let mut pending = paths
+        .iter()
+        .map(|path| path.to_path_buf())
+        .collect::<Vec<_>>();
+
+    while let Some(path) = pending.pop() {
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            continue;
+        };
+
+        if metadata.is_dir() {
+            for entry in std::fs::read_dir(&path)? {
+                pending.push(entry?.path());
+            }
+        } else {
+            times.insert(path, metadata.modified()?);
+        }
+    }
+
+    Ok(times)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(answer: u64, median: f64) -> Entry {
+        Entry {
+            puzzle: 5,
+            part: 1,
+            status: Status::Solved,
+            answer: answer.into(),
+            summary: [median; 7],
+            phases: None,
+            memory: None,
+            outliers: 0,
+            runs: vec![median],
+        }
+    }
+
+    #[test]
+    fn test_describe_change() {
+        assert_eq!(
+            describe_change(Some(&entry(35, 2.0)), &entry(35, 1.0)),
+            "Day 05 part 1: 35 (unchanged), 2.0ms -> 1000.0μs (-50.0%)"
+        );
+        assert_eq!(
+            describe_change(Some(&entry(35, 1.0)), &entry(46, 1.0)),
+            "Day 05 part 1: 46 (was 35), 1000.0μs -> 1000.0μs (+0.0%)"
+        );
+        assert_eq!(
+            describe_change(Some(&entry(35, 0.0)), &entry(35, 1.0)),
+            "Day 05 part 1: 35 (unchanged), 0ns -> 1000.0μs"
+        );
+        assert_eq!(
+            describe_change(None, &entry(35, 1.0)),
+            "Day 05 part 1: 35 (new)"
+        );
+
+        let failed = Entry {
+            status: Status::Failed,
+            ..entry(0, 0.0)
+        };
+        assert_eq!(
+            describe_change(Some(&entry(35, 1.0)), &failed),
+            "Day 05 part 1: ERROR"
+        );
+    }
+}